@@ -0,0 +1,158 @@
+// MIT License
+//
+// Copyright (c) 2025 Dmytro Prokhorov
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+use crate::root;
+
+use std::{collections::BTreeMap, fs, io, path::PathBuf};
+
+use aho_corasick::{AhoCorasick, MatchKind};
+use color_eyre::eyre::{Report, Result, WrapErr};
+use serde::{Deserialize, Serialize};
+
+/// Replacements applied to the file contents and prompts that `sel` emits, so
+/// customer names, internal hostnames and codenames never leave the machine.
+#[derive(Default, Deserialize, Serialize)]
+pub struct Mapping {
+    /// Whether names only match with the exact casing they're listed with.
+    /// By default `Acme` also matches `ACME` and `acme`.
+    #[serde(default)]
+    pub case_sensitive: bool,
+    /// Original name to the alias it is replaced with.
+    #[serde(default)]
+    pub names: BTreeMap<String, String>,
+}
+
+pub fn file_path() -> Result<PathBuf> {
+    Ok(root::storage_dir()?.join("anonymize.toml"))
+}
+
+pub fn read() -> Result<Mapping> {
+    let path = file_path()?;
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Mapping::default()),
+        Err(e) => {
+            let message = format!(
+                "failed to read anonymization mapping from {}",
+                path.display()
+            );
+            return Err(Report::new(e).wrap_err(message));
+        }
+    };
+    toml::from_str(&content).wrap_err("failed to parse anonymization mapping")
+}
+
+/// Anonymizes `text` with the mapping in `anonymize.toml`, warning about
+/// aliases that are already in it.
+pub fn apply(text: String) -> Result<String> {
+    let mapping = read()?;
+    if mapping.is_empty() {
+        return Ok(text);
+    }
+
+    let ambiguous = mapping.ambiguous_aliases(&text)?;
+    if !ambiguous.is_empty() {
+        eprintln!(
+            "Warning: aliases already present before anonymizing, restoring answers will \
+             replace them too: {}",
+            ambiguous.join(", ")
+        );
+    }
+    mapping.anonymize(&text)
+}
+
+impl Mapping {
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Replaces original names with their aliases.
+    pub fn anonymize(&self, text: &str) -> Result<String> {
+        self.replace_all(text, self.names.iter())
+    }
+
+    /// Replaces aliases with the original names, e.g. in an answer pasted back
+    /// from the model.
+    pub fn restore(&self, text: &str) -> Result<String> {
+        self.replace_all(text, self.names.iter().map(|(name, alias)| (alias, name)))
+    }
+
+    /// Aliases that already occur in `text`. Restoring an answer would turn
+    /// these into original names too, so they are worth a warning.
+    pub fn ambiguous_aliases(&self, text: &str) -> Result<Vec<&str>> {
+        let mut ambiguous = vec![];
+        for alias in self.names.values() {
+            if self.matcher([alias])?.is_match(text) {
+                ambiguous.push(alias.as_str());
+            }
+        }
+        Ok(ambiguous)
+    }
+
+    /// Replaces all occurrences in a single pass, preferring the longest match,
+    /// so a replacement is never replaced again and `Acme` doesn't clobber
+    /// `AcmeCloud`.
+    fn replace_all<'a>(
+        &self,
+        text: &str,
+        pairs: impl Iterator<Item = (&'a String, &'a String)>,
+    ) -> Result<String> {
+        let (patterns, replacements): (Vec<_>, Vec<_>) = pairs.unzip();
+        if patterns.is_empty() {
+            return Ok(text.to_owned());
+        }
+        Ok(self.matcher(patterns)?.replace_all(text, &replacements))
+    }
+
+    fn matcher<'a>(&self, patterns: impl IntoIterator<Item = &'a String>) -> Result<AhoCorasick> {
+        AhoCorasick::builder()
+            .match_kind(MatchKind::LeftmostLongest)
+            .ascii_case_insensitive(!self.case_sensitive)
+            .build(patterns)
+            .wrap_err("failed to build anonymization matcher")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapping(case_sensitive: bool) -> Mapping {
+        Mapping {
+            case_sensitive,
+            names: BTreeMap::from([
+                ("Acme".to_owned(), "Customer A".to_owned()),
+                ("AcmeCloud".to_owned(), "Product B".to_owned()),
+            ]),
+        }
+    }
+
+    #[test]
+    fn names_match_in_any_case_by_default() {
+        let text = "ACME runs acmecloud on acme.internal";
+        assert_eq!(
+            mapping(false).anonymize(text).unwrap(),
+            "Customer A runs Product B on Customer A.internal"
+        );
+        assert_eq!(mapping(true).anonymize(text).unwrap(), text);
+    }
+}
@@ -27,14 +27,24 @@ use clap::Parser;
 use select::cli::{Cli, Command};
 
 pub fn main() -> Result<()> {
-    let Cli { command } = Cli::parse();
+    let Cli { root, command } = Cli::parse();
 
     color_eyre::install()?;
 
+    if let Some(root) = root {
+        select::root::set_override(root)?;
+    }
+
     match command {
         Command::Sel(command) => command.run()?,
+        Command::Show(command) => command.run()?,
+        Command::Crate(command) => command.run()?,
         Command::Template(command) => command.run()?,
         Command::Feature(command) => command.run()?,
+        Command::Status(command) => command.run()?,
+        Command::History(command) => command.run()?,
+        Command::Anonymize(command) => command.run()?,
+        Command::ClipboardOwner(command) => command.run()?,
     }
 
     Ok(())
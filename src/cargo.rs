@@ -0,0 +1,153 @@
+// MIT License
+//
+// Copyright (c) 2025 Dmytro Prokhorov
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use color_eyre::eyre::{Result, WrapErr, eyre};
+use serde::Deserialize;
+
+/// The packages of a Cargo workspace and its dependencies, from
+/// `cargo metadata`.
+#[derive(Deserialize)]
+pub struct Metadata {
+    packages: Vec<Package>,
+    workspace_members: Vec<String>,
+    resolve: Option<Resolve>,
+}
+
+#[derive(Deserialize)]
+pub struct Package {
+    pub name: String,
+    pub version: String,
+    id: String,
+    manifest_path: PathBuf,
+}
+
+#[derive(Deserialize)]
+struct Resolve {
+    nodes: Vec<Node>,
+}
+
+#[derive(Deserialize)]
+struct Node {
+    id: String,
+    dependencies: Vec<String>,
+}
+
+impl Metadata {
+    /// Runs `cargo metadata` in `dir`. It runs offline, so dependencies are
+    /// found in the local registry cache, as they are when building.
+    pub fn load(dir: &Path) -> Result<Self> {
+        let output = Command::new("cargo")
+            .args(["metadata", "--format-version", "1", "--offline"])
+            .current_dir(dir)
+            .output()
+            .wrap_err("Failed to execute `cargo metadata`. Is cargo installed?")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(eyre!("`cargo metadata` failed: {}", stderr.trim()));
+        }
+
+        serde_json::from_slice(&output.stdout).wrap_err("failed to parse `cargo metadata` output")
+    }
+
+    /// Finds the package `name`, which may be written with `_` for `-`, of
+    /// `version` if given. If it is resolved in several versions, the one that
+    /// is a workspace member or a direct dependency of one is picked.
+    pub fn find(&self, name: &str, version: Option<&str>) -> Result<&Package> {
+        let normalize = |name: &str| name.replace('-', "_");
+        let candidates: Vec<_> = self
+            .packages
+            .iter()
+            .filter(|package| normalize(&package.name) == normalize(name))
+            .filter(|package| version.is_none_or(|version| package.version == version))
+            .collect();
+
+        let direct = self.direct_ids();
+        let preferred: Vec<_> = match candidates.iter().filter(|p| direct.contains(&p.id)).count() {
+            1 => candidates
+                .iter()
+                .filter(|p| direct.contains(&p.id))
+                .collect(),
+            _ => candidates.iter().collect(),
+        };
+
+        match preferred[..] {
+            [package] => Ok(package),
+            [] => match version {
+                Some(version) => Err(eyre!("crate '{name}' isn't resolved in version {version}")),
+                None => Err(eyre!(
+                    "no crate named '{name}' in the dependencies of the workspace"
+                )),
+            },
+            _ => {
+                let versions: Vec<_> = preferred.iter().map(|p| p.version.as_str()).collect();
+                Err(eyre!(
+                    "crate '{name}' is resolved in several versions ({}), pick one with \
+                     @crate:{name}@<version>",
+                    versions.join(", ")
+                ))
+            }
+        }
+    }
+
+    /// The workspace members, then the other packages, each sorted by name and
+    /// version.
+    pub fn packages(&self) -> Vec<&Package> {
+        let mut packages: Vec<_> = self.packages.iter().collect();
+        packages.sort_by_key(|p| (!self.is_member(p), &p.name, &p.version));
+        packages
+    }
+
+    pub fn is_member(&self, package: &Package) -> bool {
+        self.workspace_members.contains(&package.id)
+    }
+
+    /// The ids of the workspace members and their direct dependencies.
+    fn direct_ids(&self) -> HashSet<&String> {
+        let mut ids: HashSet<_> = self.workspace_members.iter().collect();
+        for node in self.resolve.iter().flat_map(|resolve| &resolve.nodes) {
+            if self.workspace_members.contains(&node.id) {
+                ids.extend(&node.dependencies);
+            }
+        }
+        ids
+    }
+}
+
+impl Package {
+    /// The directory with the package's `Cargo.toml`.
+    pub fn dir(&self) -> &Path {
+        self.manifest_path.parent().unwrap_or(&self.manifest_path)
+    }
+
+    /// How the package's files are written when it's outside the project, e.g.
+    /// `@crate:clap@4.5.41`.
+    pub fn label(&self) -> String {
+        format!("@crate:{}@{}", self.name, self.version)
+    }
+}
@@ -22,11 +22,18 @@
 
 use crate::command::*;
 
+use std::path::PathBuf;
+
 use clap::{Parser, Subcommand};
 
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
 pub struct Cli {
+    /// Project root holding `.select/`; found from the current directory by
+    /// default [env: SELECT_ROOT]
+    #[arg(long, global = true, value_name = "DIR")]
+    pub root: Option<PathBuf>,
+
     #[command(subcommand)]
     pub command: Command,
 }
@@ -37,6 +44,13 @@ pub enum Command {
     #[command(visible_alias = "s")]
     Sel(Sel),
 
+    /// Print the contents of the selected files.
+    Show(Show),
+
+    /// Add crates of the Cargo workspace and its dependencies to the global
+    /// selection.
+    Crate(Crate),
+
     /// Manage templates.
     #[command(visible_alias = "t")]
     Template(Template),
@@ -44,4 +58,19 @@ pub enum Command {
     /// Manage features.
     #[command(visible_alias = "f")]
     Feature(Feature),
+
+    /// Show files changed since the last render.
+    #[command(visible_alias = "st")]
+    Status(Status),
+
+    /// Inspect and replay recorded renders.
+    #[command(visible_alias = "h")]
+    History(History),
+
+    /// Mask names in rendered prompts and restore them in answers.
+    #[command(visible_alias = "a")]
+    Anonymize(Anonymize),
+
+    #[command(name = crate::output::clipboard::OWNER_SUBCOMMAND, hide = true)]
+    ClipboardOwner(ClipboardOwner),
 }
@@ -0,0 +1,139 @@
+// MIT License
+//
+// Copyright (c) 2025 Dmytro Prokhorov
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+use crate::{anonymize, editor};
+
+use std::{
+    fs,
+    io::{self, Read, Write},
+    path::PathBuf,
+};
+
+use clap::{Args, Subcommand};
+use color_eyre::eyre::{Result, WrapErr};
+
+#[derive(Args)]
+pub struct Anonymize {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+impl Anonymize {
+    pub fn run(self) -> Result<()> {
+        self.command.run()
+    }
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Edit the mapping of original names to aliases
+    #[command(visible_alias = "e")]
+    Edit(Edit),
+    /// List the mapping
+    #[command(visible_alias = "l")]
+    List(List),
+    /// Restore original names in text from stdin or a file, e.g. an answer from the model
+    #[command(visible_alias = "r")]
+    Restore(Restore),
+}
+
+impl Command {
+    fn run(self) -> Result<()> {
+        match self {
+            Command::Edit(cmd) => cmd.run(),
+            Command::List(cmd) => cmd.run(),
+            Command::Restore(cmd) => cmd.run(),
+        }
+    }
+}
+
+#[derive(Args)]
+pub struct Edit {}
+
+impl Edit {
+    fn run(self) -> Result<()> {
+        let path = anonymize::file_path()?;
+        if !path.exists() {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).wrap_err_with(|| {
+                    format!("failed to create directories for {}", parent.display())
+                })?;
+            }
+            let placeholder = "# Names replaced in everything `sel` emits, in any casing.\n\
+                               # Use `sel anonymize restore` to map the aliases back.\n\
+                               # case_sensitive = true\n\
+                               [names]\n\
+                               # \"Acme Corp\" = \"Customer A\"\n";
+            fs::write(&path, placeholder)
+                .wrap_err_with(|| format!("failed to write {}", path.display()))?;
+        }
+        editor::open_in_vim(&path, 4)?;
+        // Make sure the mapping still parses before the next render relies on it.
+        anonymize::read()?;
+        println!("Anonymization mapping updated.");
+        Ok(())
+    }
+}
+
+#[derive(Args)]
+pub struct List {}
+
+impl List {
+    fn run(self) -> Result<()> {
+        let mapping = anonymize::read()?;
+        if mapping.is_empty() {
+            println!("No names are anonymized.");
+        } else {
+            for (name, alias) in &mapping.names {
+                println!("{name} -> {alias}");
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Args)]
+pub struct Restore {
+    /// File to read instead of stdin
+    file: Option<PathBuf>,
+}
+
+impl Restore {
+    fn run(self) -> Result<()> {
+        let text = match self.file {
+            Some(path) => fs::read_to_string(&path)
+                .wrap_err_with(|| format!("failed to read {}", path.display()))?,
+            None => {
+                let mut text = String::new();
+                io::stdin()
+                    .read_to_string(&mut text)
+                    .wrap_err("failed to read stdin")?;
+                text
+            }
+        };
+
+        let restored = anonymize::read()?.restore(&text)?;
+        io::stdout()
+            .write_all(restored.as_bytes())
+            .wrap_err("failed to write to stdout")
+    }
+}
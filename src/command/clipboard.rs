@@ -0,0 +1,54 @@
+// MIT License
+//
+// Copyright (c) 2025 Dmytro Prokhorov
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+use crate::{config::clipboard::ClipboardTarget, output::clipboard};
+
+use std::io::{self, Read};
+
+use clap::Args;
+use color_eyre::eyre::{Result, WrapErr};
+
+/// Reads the contents from stdin and owns the selection until another
+/// application takes it. Started by the `background` clipboard mode.
+#[derive(Args)]
+pub struct ClipboardOwner {
+    /// Own the PRIMARY selection instead of the clipboard
+    #[arg(long)]
+    primary: bool,
+}
+
+impl ClipboardOwner {
+    pub fn run(self) -> Result<()> {
+        let mut content = String::new();
+        io::stdin()
+            .read_to_string(&mut content)
+            .wrap_err("failed to read clipboard contents from stdin")?;
+
+        let target = if self.primary {
+            ClipboardTarget::Primary
+        } else {
+            ClipboardTarget::Clipboard
+        };
+
+        clipboard::hold(content, target)
+    }
+}
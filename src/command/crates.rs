@@ -0,0 +1,107 @@
+// MIT License
+//
+// Copyright (c) 2025 Dmytro Prokhorov
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+use crate::{
+    cargo::Metadata,
+    command::utils,
+    config::{
+        Config,
+        selection::{CrateSource, SelectedPath, View},
+    },
+    root, token,
+};
+
+use std::path::PathBuf;
+
+use clap::Args;
+use color_eyre::eyre::{Result, eyre};
+
+#[derive(Args)]
+pub struct Crate {
+    /// Crates to add to the global selection, like `clap`, `serde@1.0.219` or
+    /// `serde/src/de`. Lists the crates of the workspace and its dependencies
+    /// if none are given
+    names: Vec<String>,
+
+    /// Present the crates' files with this view, e.g. `skeleton`
+    #[arg(long, value_parser = parse_view)]
+    view: Option<View>,
+}
+
+impl Crate {
+    pub fn run(self) -> Result<()> {
+        let metadata = Metadata::load(&root::project_root()?)?;
+
+        if self.names.is_empty() {
+            for package in metadata.packages() {
+                let member = if metadata.is_member(package) {
+                    " (workspace member)"
+                } else {
+                    ""
+                };
+                println!("{} {}{member}", package.name, package.version);
+            }
+            return Ok(());
+        }
+
+        let mut config = Config::read()?;
+        let mut selection = config.selection.take().unwrap_or_default();
+
+        for name in &self.names {
+            let entry = format!("@crate:{name}");
+            let source: CrateSource = entry.parse().map_err(|()| {
+                eyre!("invalid crate '{name}', expected <name>[@<version>][/<path>]")
+            })?;
+            // Fail on unknown crates now rather than on the next render.
+            let package = metadata.find(&source.name, source.version.as_deref())?;
+            eprintln!(
+                "Added {} {} from {}",
+                package.name,
+                package.version,
+                package.dir().display()
+            );
+
+            let selected_path = SelectedPath::new(PathBuf::from(entry), true).with_view(self.view);
+            selection
+                .0
+                .retain(|existing| existing.path != selected_path.path);
+            selection.0.insert(selected_path);
+        }
+
+        config.selection.replace(selection.clone());
+        config.write()?;
+
+        let files_content = utils::get_selected_files_content_as_string(&selection, None)?;
+        let token_count = token::estimate(&files_content);
+        eprintln!(
+            "{} paths selected. Approximate token count: {token_count}",
+            selection.0.len()
+        );
+
+        Ok(())
+    }
+}
+
+fn parse_view(view: &str) -> Result<View, String> {
+    view.parse()
+        .map_err(|()| format!("unknown view '{view}', expected full, skeleton or outline"))
+}
@@ -2,7 +2,7 @@ use crate::{
     command::utils,
     editor,
     feature::{self, FeatureName},
-    git, token,
+    root, token,
 };
 
 use clap::{Args, Subcommand};
@@ -118,23 +118,23 @@ impl Select {
             ));
         }
 
-        let git_root = git::repo_root()?;
+        let project_root = root::project_root()?;
         let previous_selection = feature::read_selection(&name)?.unwrap_or_default();
 
         let selection =
-            utils::interactive_selection(&self.roots, Some(previous_selection), &git_root)?;
+            utils::interactive_selection(&self.roots, Some(previous_selection), &project_root)?;
 
         feature::write_selection(&name, &selection)?;
 
         let selection_len = selection.0.len();
         if selection_len > 0 {
-            let files_content = utils::get_selected_files_content_as_string(&selection)?;
+            let files_content = utils::get_selected_files_content_as_string(&selection, None)?;
             let token_count = token::estimate(&files_content);
-            println!(
+            eprintln!(
                 "Selection for feature '{name}' updated: {selection_len} paths. Approximate token count: {token_count}"
             );
         } else {
-            println!("Selection for feature '{name}' is empty: 0 paths selected");
+            eprintln!("Selection for feature '{name}' is empty: 0 paths selected");
         }
 
         Ok(())
@@ -0,0 +1,194 @@
+// MIT License
+//
+// Copyright (c) 2025 Dmytro Prokhorov
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+use crate::{
+    command::sink::SinkArgs,
+    history::{self, Manifest, RenderId},
+    output, root,
+};
+
+use std::fs;
+
+use clap::{Args, Subcommand};
+use color_eyre::eyre::Result;
+
+#[derive(Args)]
+pub struct History {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+impl History {
+    pub fn run(self) -> Result<()> {
+        match self.command {
+            Some(command) => command.run(),
+            None => List {}.run(),
+        }
+    }
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// List recorded renders
+    #[command(visible_alias = "l")]
+    List(List),
+    /// Show the manifest of a render
+    #[command(visible_alias = "s")]
+    Show(Show),
+    /// Output the exact prompt of a render again
+    #[command(visible_alias = "r")]
+    Replay(Replay),
+}
+
+impl Command {
+    fn run(self) -> Result<()> {
+        match self {
+            Command::List(cmd) => cmd.run(),
+            Command::Show(cmd) => cmd.run(),
+            Command::Replay(cmd) => cmd.run(),
+        }
+    }
+}
+
+#[derive(Args)]
+pub struct List {}
+
+impl List {
+    fn run(self) -> Result<()> {
+        let manifests = history::list()?;
+        if manifests.is_empty() {
+            println!("No renders recorded.");
+            return Ok(());
+        }
+        for manifest in manifests.iter().rev() {
+            let task_summary = manifest.task.lines().next().unwrap_or_default();
+            println!(
+                "{:>4}  {}  {}{}  {} tokens  {}",
+                manifest.id,
+                humantime::format_rfc3339_seconds(manifest.time()),
+                manifest.template,
+                manifest
+                    .feature
+                    .as_ref()
+                    .map(|f| format!(" [{f}]"))
+                    .unwrap_or_default(),
+                manifest.token_count,
+                task_summary,
+            );
+        }
+        Ok(())
+    }
+}
+
+#[derive(Args)]
+pub struct Show {
+    /// ID of the render
+    id: RenderId,
+}
+
+impl Show {
+    fn run(self) -> Result<()> {
+        let manifest = history::read(self.id)?;
+        let changed = changed_files(&manifest)?;
+
+        println!("Render:   {}", manifest.id);
+        println!(
+            "Time:     {}",
+            humantime::format_rfc3339_seconds(manifest.time())
+        );
+        println!("Template: {}", manifest.template);
+        if let Some(ref feature) = manifest.feature {
+            println!("Feature:  {feature}");
+        }
+        if let Some(ref model) = manifest.model {
+            println!("Model:    {model}");
+        }
+        if let Some(ref rev) = manifest.rev {
+            println!("Revision: {rev}");
+        }
+        println!("Tokens:   {}", manifest.token_count);
+        println!("Files:");
+        for file in &manifest.files {
+            let marker = if changed.contains(&file.path) {
+                " (changed since)"
+            } else {
+                ""
+            };
+            println!(
+                "  {}  {}{}",
+                file.hash.get(..12).unwrap_or(&file.hash),
+                file.path.display(),
+                marker
+            );
+        }
+        println!("Task:");
+        for line in manifest.task.lines() {
+            println!("  {line}");
+        }
+        Ok(())
+    }
+}
+
+#[derive(Args)]
+pub struct Replay {
+    /// ID of the render
+    id: RenderId,
+
+    #[command(flatten)]
+    output: SinkArgs,
+}
+
+impl Replay {
+    fn run(self) -> Result<()> {
+        let manifest = history::read(self.id)?;
+        let prompt = history::read_prompt(self.id)?;
+
+        output::emit(&self.output.sinks()?, &prompt)?;
+
+        let changed = changed_files(&manifest)?;
+        if !changed.is_empty() {
+            eprintln!(
+                "{} of {} files have changed since render {}.",
+                changed.len(),
+                manifest.files.len(),
+                manifest.id
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Returns the recorded files whose current content no longer matches the manifest.
+fn changed_files(manifest: &Manifest) -> Result<Vec<std::path::PathBuf>> {
+    let project_root = root::project_root()?;
+    let changed = manifest
+        .files
+        .iter()
+        .filter(|file| {
+            fs::read(project_root.join(&file.path))
+                .map(|content| history::hash_content(&content) != file.hash)
+                .unwrap_or(true)
+        })
+        .map(|file| file.path.clone())
+        .collect();
+    Ok(changed)
+}
@@ -20,16 +20,42 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
 // THE SOFTWARE.
 
+pub mod anonymize;
+
+pub mod clipboard;
+
+pub mod crates;
+
 pub mod sel;
 
+pub mod show;
+
+pub mod sink;
+
+pub mod status;
+
 pub mod utils;
 
 pub mod template;
 
 pub mod feature;
 
+pub mod history;
+
+pub use anonymize::Anonymize;
+
+pub use clipboard::ClipboardOwner;
+
+pub use crates::Crate;
+
 pub use sel::Sel;
 
+pub use show::Show;
+
+pub use status::Status;
+
 pub use template::Template;
 
 pub use feature::Feature;
+
+pub use history::History;
@@ -23,7 +23,7 @@
 use crate::{
     command::utils,
     config::Config,
-    git, token,
+    root, token,
 };
 
 use std::path::PathBuf;
@@ -41,10 +41,10 @@ pub struct Sel {
 impl Sel {
     pub fn run(self) -> Result<()> {
         let mut config = Config::read()?;
-        let git_root = git::repo_root()?;
+        let project_root = root::project_root()?;
 
         let selection =
-            utils::interactive_selection(&self.roots, config.selection.take(), &git_root)?;
+            utils::interactive_selection(&self.roots, config.selection.take(), &project_root)?;
 
         let selection_len = selection.0.len();
 
@@ -53,11 +53,11 @@ impl Sel {
         config.write()?;
 
         if selection_len > 0 {
-            let files_content = utils::get_selected_files_content_as_string(&selection)?;
+            let files_content = utils::get_selected_files_content_as_string(&selection, None)?;
             let token_count = token::estimate(&files_content);
-            println!("{selection_len} paths selected. Approximate token count: {token_count}");
+            eprintln!("{selection_len} paths selected. Approximate token count: {token_count}");
         } else {
-            println!("0 paths selected");
+            eprintln!("0 paths selected");
         }
 
         Ok(())
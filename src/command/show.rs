@@ -0,0 +1,67 @@
+// MIT License
+//
+// Copyright (c) 2025 Dmytro Prokhorov
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+use crate::{
+    command::utils,
+    feature::{self, FeatureName},
+    git, root,
+};
+
+use clap::Args;
+use color_eyre::eyre::{Result, eyre};
+
+#[derive(Args)]
+pub struct Show {
+    /// Show a feature's selection instead of the global one
+    #[arg(short, long)]
+    feature: Option<String>,
+
+    /// Read file contents from a git revision instead of the working tree
+    #[arg(long, value_name = "COMMIT")]
+    rev: Option<String>,
+}
+
+impl Show {
+    pub fn run(self) -> Result<()> {
+        let selection = match self.feature.map(FeatureName::new) {
+            Some(ref name) => {
+                if !feature::exists(name)? {
+                    return Err(eyre!("Feature '{}' does not exist.", name));
+                }
+                feature::read_selection(name)?.unwrap_or_default()
+            }
+            None => utils::get_global_selection()?,
+        };
+
+        let rev = self
+            .rev
+            .map(|rev| git::resolve_commit(&root::project_root()?, &rev))
+            .transpose()?;
+
+        print!(
+            "{}",
+            utils::get_selected_files_content_as_string(&selection, rev.as_deref())?
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+// MIT License
+//
+// Copyright (c) 2025 Dmytro Prokhorov
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+use crate::{
+    config::{Config, clipboard::ClipboardTarget},
+    output::Sink,
+};
+
+use std::path::PathBuf;
+
+use clap::Args;
+use color_eyre::eyre::Result;
+
+/// Where a rendered prompt should go. Without any flags, it is copied to the clipboard.
+#[derive(Args)]
+pub struct SinkArgs {
+    /// Copy the prompt to the clipboard (the default when no other output is given)
+    #[arg(long)]
+    clipboard: bool,
+
+    /// Copy to the PRIMARY selection instead of the clipboard (X11/Wayland)
+    #[arg(long)]
+    primary: bool,
+
+    /// Write the prompt to stdout
+    #[arg(long)]
+    stdout: bool,
+
+    /// Write the prompt to a file
+    #[arg(long, value_name = "PATH")]
+    out: Vec<PathBuf>,
+
+    /// Pipe the prompt into a shell command, e.g. `--pipe "llm -m gpt-4o"`
+    #[arg(long, value_name = "COMMAND")]
+    pipe: Vec<String>,
+}
+
+impl SinkArgs {
+    pub fn sinks(&self) -> Result<Vec<Sink>> {
+        let mut sinks = vec![];
+        if self.stdout {
+            sinks.push(Sink::Stdout);
+        }
+        sinks.extend(self.out.iter().cloned().map(Sink::File));
+        sinks.extend(self.pipe.iter().cloned().map(Sink::Pipe));
+        if self.clipboard || self.primary || sinks.is_empty() {
+            let mut config = Config::read()?.clipboard.unwrap_or_default();
+            if self.primary {
+                config.target = ClipboardTarget::Primary;
+            }
+            sinks.push(Sink::Clipboard(config));
+        }
+        Ok(sinks)
+    }
+}
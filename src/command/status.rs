@@ -0,0 +1,107 @@
+// MIT License
+//
+// Copyright (c) 2025 Dmytro Prokhorov
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+use crate::{
+    command::utils,
+    feature::{self, FeatureName},
+    history::{self, RecordedFile},
+    root,
+};
+
+use std::{collections::BTreeMap, fs, path::PathBuf};
+
+use clap::Args;
+use color_eyre::eyre::{Result, WrapErr, eyre};
+
+#[derive(Args)]
+pub struct Status {
+    /// Compare against the last render of a feature instead of the global selection
+    #[arg(short, long)]
+    feature: Option<String>,
+}
+
+impl Status {
+    pub fn run(self) -> Result<()> {
+        let feature_name = self.feature.map(FeatureName::new);
+
+        let selection = match feature_name {
+            Some(ref name) => {
+                if !feature::exists(name)? {
+                    return Err(eyre!("Feature '{}' does not exist.", name));
+                }
+                feature::read_selection(name)?.unwrap_or_default()
+            }
+            None => utils::get_global_selection()?,
+        };
+
+        let Some(manifest) = history::latest(feature_name.as_ref())? else {
+            match feature_name {
+                Some(name) => println!("Feature '{name}' has not been rendered yet."),
+                None => println!("The global selection has not been rendered yet."),
+            }
+            return Ok(());
+        };
+
+        let rendered_at = humantime::format_rfc3339_seconds(manifest.time());
+
+        let project_root = root::project_root()?;
+        let mut current = BTreeMap::new();
+        utils::walk_selected_files(&selection, |abs_path, _rel_path, _view| {
+            let content = fs::read(abs_path)
+                .wrap_err_with(|| format!("failed to read file {}", abs_path.display()))?;
+            let file = RecordedFile::new(&project_root, abs_path, &content);
+            current.insert(file.path, file.hash);
+            Ok(())
+        })?;
+
+        let previous: BTreeMap<PathBuf, String> = manifest
+            .files
+            .into_iter()
+            .map(|file| (file.path, file.hash))
+            .collect();
+
+        let mut changes = vec![];
+        for (path, hash) in &current {
+            match previous.get(path) {
+                Some(previous_hash) if previous_hash != hash => changes.push(("modified", path)),
+                Some(_) => {}
+                None => changes.push(("added", path)),
+            }
+        }
+        for path in previous.keys() {
+            if !current.contains_key(path) {
+                changes.push(("removed", path));
+            }
+        }
+
+        if changes.is_empty() {
+            println!("Up to date with render {} ({rendered_at}).", manifest.id);
+        } else {
+            println!("Changes since render {} ({rendered_at}):", manifest.id);
+            for (kind, path) in changes {
+                println!("  {:<9} {}", format!("{kind}:"), path.display());
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -59,7 +59,7 @@ pub enum Command {
     List(List),
     /// Render a template with a task
     #[command(visible_alias = "r")]
-    Render(Render),
+    Render(Box<Render>),
 }
 
 impl Command {
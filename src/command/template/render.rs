@@ -1,17 +1,28 @@
 use crate::{
-    command::utils,
+    anonymize,
+    command::{sink::SinkArgs, utils},
+    config::Config,
+    content::{FileContent, FileProcessor},
     editor,
     feature::{self, FeatureName},
-    output,
+    git::{self, FileStatus},
+    history::{self, NewRender, RecordedFile},
+    output, root, secrets, task,
     template::{self, TemplateName},
     token,
 };
 
-use std::{fmt::Write, fs};
+use std::{
+    collections::HashMap,
+    env,
+    fmt::Write,
+    path::{Path, PathBuf},
+};
 
-use clap::Args;
+use clap::{Args, ValueEnum};
 use color_eyre::eyre::{eyre, Result, WrapErr};
 use serde::Serialize;
+use similar::TextDiff;
 
 #[derive(Args)]
 pub struct Render {
@@ -21,11 +32,88 @@ pub struct Render {
     /// Use a feature's selection and spec
     #[arg(short, long)]
     feature: Option<String>,
+
+    /// Model profile the prompt is meant for; recorded in the render history
+    #[arg(short, long)]
+    model: Option<String>,
+
+    /// Re-render with the most recent task instead of opening the editor
+    #[arg(long, conflicts_with = "from_history")]
+    last: bool,
+
+    /// Re-render with the N-th most recent task (1 is the last one)
+    #[arg(long, value_name = "N")]
+    from_history: Option<usize>,
+
+    /// Only include files that changed since the previous render of this
+    /// feature (or of the global selection)
+    #[arg(long)]
+    since_last: bool,
+
+    /// How changed files are emitted with `--since-last`
+    #[arg(long, value_enum, default_value_t = DeltaFormat::Full, requires = "since_last")]
+    delta_format: DeltaFormat,
+
+    /// Strip comments and redundant blank lines from source files
+    #[arg(long)]
+    minify: bool,
+
+    /// Keep doc comments and docstrings when minifying
+    #[arg(long, requires = "minify")]
+    keep_docs: bool,
+
+    /// Include a diff of the selected paths against REV (HEAD by default),
+    /// available to templates as `diff`
+    #[arg(long, value_name = "REV", num_args = 0..=1, default_missing_value = "HEAD")]
+    diff: Option<String>,
+
+    /// Diff the staged changes instead of the working tree
+    #[arg(long, requires = "diff")]
+    staged: bool,
+
+    /// Include the last N commits touching the selected paths, available to
+    /// templates as `git_log`
+    #[arg(long, value_name = "N")]
+    log: Option<usize>,
+
+    /// Include a diffstat with each commit of `--log`
+    #[arg(long, requires = "log")]
+    log_stat: bool,
+
+    /// Drop older commits of `--log` beyond this many tokens
+    #[arg(long, value_name = "TOKENS", default_value_t = 2000, requires = "log")]
+    log_tokens: usize,
+
+    /// Append the uncommitted hunks of modified files after their content
+    #[arg(long, conflicts_with = "rev")]
+    hunks: bool,
+
+    /// Read file contents from a git revision instead of the working tree
+    #[arg(long, value_name = "COMMIT")]
+    rev: Option<String>,
+
+    /// Files or directories shown in full even where a view like
+    /// `skeleton` applies
+    #[arg(long, value_name = "PATH", num_args = 1..)]
+    focus: Vec<PathBuf>,
+
+    #[command(flatten)]
+    output: SinkArgs,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DeltaFormat {
+    /// The full content of each changed file
+    Full,
+    /// A unified diff against the previously rendered content
+    Diff,
 }
 
 #[derive(Serialize)]
 struct RenderContext {
     task: String,
+    diff: Option<String>,
+    git_log: Option<String>,
 }
 
 impl Render {
@@ -35,21 +123,106 @@ impl Render {
             return Err(eyre!("Template '{}' does not exist.", template_name));
         }
 
-        let (selection, spec) = if let Some(feature_name_str) = self.feature {
-            let feature_name = FeatureName::new(feature_name_str);
-            if !feature::exists(&feature_name)? {
+        let feature_name = self.feature.clone().map(FeatureName::new);
+
+        let (selection, spec) = if let Some(ref feature_name) = feature_name {
+            if !feature::exists(feature_name)? {
                 return Err(eyre!("Feature '{}' does not exist.", feature_name));
             }
-            let selection = feature::read_selection(&feature_name)?.unwrap_or_default();
-            let spec = feature::read_spec(&feature_name)?;
+            let selection = feature::read_selection(feature_name)?.unwrap_or_default();
+            let spec = feature::read_spec(feature_name)?;
             (selection, spec)
         } else {
             (utils::get_global_selection()?, None)
         };
 
-        let task = get_task_from_editor()?;
+        let base = if self.since_last {
+            let base = history::latest(feature_name.as_ref())?;
+            if base.is_none() {
+                eprintln!("No previous render to compare against; including all files.");
+            }
+            base
+        } else {
+            None
+        };
+        let previous_files: Option<HashMap<_, _>> = base.as_ref().map(|base| {
+            base.files
+                .iter()
+                .map(|file| (file.path.clone(), (file.hash.clone(), file.object.clone())))
+                .collect()
+        });
+
+        let from_editor = !self.last && self.from_history.is_none();
+        let task = match (self.last, self.from_history) {
+            (true, _) => task::from_history(1)?,
+            (false, Some(n)) => task::from_history(n)?,
+            (false, None) => {
+                let task = get_task_from_editor(task::read_draft()?.as_deref())?;
+                // Keep the task around until the render succeeds, so a failure
+                // below doesn't lose what was typed.
+                task::write_draft(&task)?;
+                task
+            }
+        };
+        // Recorded before rendering, so `--last` can retry a failed render.
+        task::push_history(&task)?;
+
+        let project_root = root::project_root()?;
+
+        let rev = self
+            .rev
+            .as_deref()
+            .map(|rev| git::resolve_commit(&project_root, rev))
+            .transpose()?;
+
+        let config = Config::read()?;
+        let secrets_config = config.secrets.unwrap_or_default();
+        let mut processor = FileProcessor::new(config.files.unwrap_or_default(), &secrets_config)?;
+        if self.minify {
+            processor = processor.with_minify(self.keep_docs);
+        }
+        if !self.focus.is_empty() {
+            processor = processor.with_focus(focus_paths(&project_root, &self.focus)?);
+        }
+
+        let diff = match self.diff {
+            Some(ref rev) => {
+                let mut pathspecs = utils::selection_pathspecs(&project_root, &selection, None)?;
+                let files = git::diff_files(&project_root, rev, self.staged, &pathspecs)?;
+                pathspecs.extend(utils::exclude_pathspecs(&project_root, &selection, &files)?);
+                let diff = git::diff(&project_root, rev, self.staged, &pathspecs)?;
+                if diff.is_empty() {
+                    eprintln!("No changes against {rev} in the selected paths.");
+                }
+                Some(processor.scan(Path::new("git diff"), diff))
+            }
+            None => None,
+        };
+        let git_log = match self.log {
+            Some(count) => {
+                let pathspecs =
+                    utils::selection_pathspecs(&project_root, &selection, rev.as_deref())?;
+                let rev = rev.as_deref().unwrap_or("HEAD");
+                let commits: Vec<_> =
+                    git::log(&project_root, rev, count, self.log_stat, &pathspecs)?
+                        .into_iter()
+                        // Messages can quote secrets too, and redacting changes their size.
+                        .map(|commit| processor.scan(Path::new("git log"), commit))
+                        .collect();
+                Some(limit_log(&commits, self.log_tokens))
+            }
+            None => None,
+        };
 
-        let context = RenderContext { task };
+        // A template that places these itself doesn't get separate blocks.
+        let diff_block = separate_block(&template_name, "diff", &diff)?;
+        let git_log_block = separate_block(&template_name, "git_log", &git_log)?;
+
+        let context = RenderContext {
+            task: task.clone(),
+            diff,
+            git_log,
+        };
 
         let rendered_template = template::render(&template_name, &context)?;
 
@@ -67,17 +240,107 @@ impl Render {
         writeln!(&mut buf, "{}", rendered_template).wrap_err("failed to write to buffer")?;
 
         // 2. Print selected files
-        utils::walk_selected_files(&selection, |abs_path, rel_path| {
-            write!(&mut buf, "<file path=\"{}\">\n", rel_path.display())
-                .wrap_err("failed to write file header to buffer")?;
+        let mut files = vec![];
+        // Uncommitted changes only make sense for the working tree of a repository.
+        let statuses = match rev {
+            None if git::is_repo(&project_root) => git::statuses(&project_root)?,
+            _ => HashMap::new(),
+        };
+        utils::read_selected_files(
+            &selection,
+            rev.as_deref(),
+            |abs_path, rel_path, view, bytes| {
+                let mut file = RecordedFile::new(&project_root, abs_path, &bytes);
+                let file_content = processor.process(&file.path, &bytes, view)?;
 
-            let file_content = fs::read_to_string(abs_path)
-                .wrap_err_with(|| format!("failed to read file {}", abs_path.display()))?;
-            buf.push_str(&file_content);
+                if let FileContent::Text(ref text) = file_content {
+                    file.object = Some(history::write_object(text.as_bytes())?);
+                }
 
-            write!(&mut buf, "</file>\n").wrap_err("failed to write file footer to buffer")?;
-            Ok(())
-        })?;
+                let previous = previous_files
+                    .as_ref()
+                    .and_then(|previous| previous.get(&file.path));
+
+                if previous.is_some_and(|(hash, _)| *hash == file.hash) {
+                    // Unchanged since the previous render.
+                    files.push(file);
+                    return Ok(());
+                }
+
+                let old_text = match previous {
+                    Some((_, Some(object))) if self.delta_format == DeltaFormat::Diff => {
+                        history::read_object(object)?
+                    }
+                    _ => None,
+                };
+
+                match (old_text, &file_content) {
+                    (Some(old_text), FileContent::Text(text)) => {
+                        write_diff(&mut buf, rel_path, &old_text, text)?
+                    }
+                    _ => {
+                        let status = statuses.get(&file.path).copied();
+                        utils::write_file_block(
+                            &mut buf,
+                            rel_path,
+                            &file_content,
+                            status,
+                            processor.config(),
+                        )?;
+
+                        if self.hunks && status == Some(FileStatus::Modified) {
+                            // Names like `app/[id].tsx` mustn't match as globs.
+                            let pathspec = format!(":(literal){}", file.path.display());
+                            let diff = git::diff(&project_root, "HEAD", false, &[pathspec])?;
+                            let diff = processor.scan(&file.path, diff);
+                            write_hunks(&mut buf, rel_path, &diff)?;
+                        }
+                    }
+                }
+
+                files.push(file);
+                Ok(())
+            },
+        )?;
+
+        if let Some(previous_files) = previous_files {
+            let current_dir = env::current_dir().wrap_err("failed to get current dir")?;
+            let mut deleted: Vec<_> = previous_files
+                .into_keys()
+                .filter(|path| !files.iter().any(|file| file.path == *path))
+                .collect();
+            deleted.sort();
+            for path in deleted {
+                let abs_path = project_root.join(&path);
+                let rel_path = pathdiff::diff_paths(&abs_path, &current_dir).unwrap_or(abs_path);
+                writeln!(&mut buf, "<deleted path=\"{}\" />", rel_path.display())
+                    .wrap_err("failed to write to buffer")?;
+            }
+        }
+
+        if let Some(diff) = diff_block {
+            let rev = self.diff.as_deref().unwrap_or("HEAD");
+            let staged = if self.staged { " staged=\"true\"" } else { "" };
+            writeln!(&mut buf, "<git_diff rev=\"{rev}\"{staged}>")
+                .wrap_err("failed to write to buffer")?;
+            buf.push_str(&diff);
+            writeln!(&mut buf, "</git_diff>").wrap_err("failed to write to buffer")?;
+        }
+
+        if let Some(git_log) = git_log_block {
+            writeln!(&mut buf, "<git_log>").wrap_err("failed to write to buffer")?;
+            buf.push_str(&git_log);
+            writeln!(&mut buf, "</git_log>").wrap_err("failed to write to buffer")?;
+        }
+
+        if let Some((before, after)) = processor.minified_tokens() {
+            eprintln!(
+                "Minified files: {before} -> {after} tokens ({} saved).",
+                before.0.saturating_sub(after.0)
+            );
+        }
+
+        secrets::report(processor.findings(), secrets_config.action)?;
 
         // 3. Print spec and rendered template again
         if let Some(ref s) = spec_block {
@@ -85,22 +348,129 @@ impl Render {
         }
         write!(&mut buf, "{}", rendered_template).wrap_err("failed to write to buffer")?;
 
+        let buf = anonymize::apply(buf)?;
+
         let token_count = token::estimate(&buf);
 
-        output::copy_to_clipboard(buf)?;
+        output::emit(&self.output.sinks()?, &buf)?;
+
+        let render = NewRender {
+            template: template_name.to_string(),
+            feature: self.feature,
+            model: self.model,
+            base: base.map(|base| base.id),
+            rev,
+            token_count,
+            task: task.clone(),
+            files,
+        };
+        let render_id = history::record(render, &buf)?;
+
+        // A draft is only used up by the render it was typed for.
+        if from_editor {
+            task::delete_draft()?;
+        }
 
         eprintln!("Approximate token count: {token_count}");
+        eprintln!("Recorded as render {render_id}.");
 
         Ok(())
     }
 }
 
-fn get_task_from_editor() -> Result<String> {
+/// Returns `value` if it should get a block of its own, i.e. it isn't empty
+/// and the template doesn't refer to it as `variable`.
+fn separate_block(
+    template_name: &TemplateName,
+    variable: &str,
+    value: &Option<String>,
+) -> Result<Option<String>> {
+    match value {
+        Some(value) if !value.is_empty() && !template::uses_variable(template_name, variable)? => {
+            Ok(Some(value.clone()))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Joins as many of the newest `commits` as fit in `max_tokens`.
+fn limit_log(commits: &[String], max_tokens: usize) -> String {
+    let mut log = String::new();
+    let mut tokens = 0;
+    for (i, commit) in commits.iter().enumerate() {
+        let commit_tokens = token::estimate(commit).0;
+        if tokens + commit_tokens > max_tokens {
+            eprintln!(
+                "Left out {} older commit(s) to stay within {max_tokens} tokens.",
+                commits.len() - i
+            );
+            break;
+        }
+        tokens += commit_tokens;
+        if !log.is_empty() {
+            log.push('\n');
+        }
+        log.push_str(commit);
+        log.push('\n');
+    }
+    log
+}
+
+/// Resolves `--focus` paths to paths relative to the repository root.
+fn focus_paths(project_root: &Path, paths: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    paths
+        .iter()
+        .map(|path| {
+            let canonical = path
+                .canonicalize()
+                .wrap_err_with(|| format!("failed to find focus path {}", path.display()))?;
+            canonical
+                .strip_prefix(project_root)
+                .map(Path::to_path_buf)
+                .map_err(|_| eyre!("{} is outside the repository", path.display()))
+        })
+        .collect()
+}
+
+/// Appends the hunks of a `git diff` for a file, without its header.
+fn write_hunks(buf: &mut String, rel_path: &Path, diff: &str) -> Result<()> {
+    let Some(start) = diff.find("\n@@").map(|start| start + 1) else {
+        return Ok(());
+    };
+
+    writeln!(buf, "<hunks path=\"{}\">", rel_path.display())
+        .wrap_err("failed to write hunks header to buffer")?;
+    buf.push_str(&diff[start..]);
+    writeln!(buf, "</hunks>").wrap_err("failed to write hunks footer to buffer")
+}
+
+fn write_diff(buf: &mut String, rel_path: &Path, old: &str, new: &str) -> Result<()> {
+    let path = rel_path.display().to_string();
+    let diff = TextDiff::from_lines(old, new);
+
+    writeln!(buf, "<diff path=\"{path}\">").wrap_err("failed to write diff header to buffer")?;
+    write!(
+        buf,
+        "{}",
+        diff.unified_diff().context_radius(3).header(&path, &path)
+    )
+    .wrap_err("failed to write diff to buffer")?;
+    writeln!(buf, "</diff>").wrap_err("failed to write diff footer to buffer")
+}
+
+fn get_task_from_editor(draft: Option<&str>) -> Result<String> {
     const HEADER: &str =
         "<!-- Enter your task description. Content in markdown comments will be ignored. -->\n\n";
+    const DRAFT_HEADER: &str = "<!-- Restored the task from a render that did not finish. -->\n";
     let cursor_line = HEADER.lines().count();
 
-    let content = editor::get_user_input_from_file_content(HEADER, cursor_line, Some(".md"))?;
+    let initial_content = match draft {
+        Some(draft) if !draft.trim().is_empty() => format!("{HEADER}{DRAFT_HEADER}{draft}\n"),
+        _ => HEADER.to_owned(),
+    };
+
+    let content =
+        editor::get_user_input_from_file_content(&initial_content, cursor_line, Some(".md"))?;
 
     let cleaned_content = remove_comments_from_string(content);
 
@@ -114,16 +484,12 @@ fn get_task_from_editor() -> Result<String> {
 }
 
 fn remove_comments_from_string(mut text: String) -> String {
-    loop {
-        if let Some(start) = text.find("<!--") {
-            if let Some(end) = text[start..].find("-->") {
-                text.replace_range(start..start + end + 3, "");
-            } else {
-                // unclosed comment, remove it to avoid infinite loop
-                text.replace_range(start..start + 4, "");
-            }
+    while let Some(start) = text.find("<!--") {
+        if let Some(end) = text[start..].find("-->") {
+            text.replace_range(start..start + end + 3, "");
         } else {
-            break;
+            // unclosed comment, remove it to avoid infinite loop
+            text.replace_range(start..start + 4, "");
         }
     }
     text
@@ -1,13 +1,28 @@
 use crate::{
-    config::{selection::SelectedPath, Config, Selection},
-    constants::CUSTOM_IGNORE_FILENAME,
-    editor, git,
+    anonymize,
+    cargo::{Metadata, Package},
+    config::{
+        files::BinaryPolicy,
+        selection::{CrateSource, GitSource, SelectedPath, View},
+        Config, FilesConfig, Selection,
+    },
+    constants::{CUSTOM_IGNORE_FILENAME, STORAGE_DIRNAME},
+    content::{FileContent, FileProcessor},
+    editor,
+    exclude::{self, Exclusions, Reason},
+    git::{self, FileStatus},
+    module_graph::{self, SourceTree},
+    root::{self, NamedRoots},
+    secrets,
 };
 use color_eyre::eyre::{eyre, Result, WrapErr};
-use ignore::WalkBuilder;
+use ignore::{
+    gitignore::{Gitignore, GitignoreBuilder},
+    WalkBuilder,
+};
 use pathdiff::diff_paths;
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeSet, HashMap, HashSet},
     env,
     fmt::Write,
     fs,
@@ -16,79 +31,780 @@ use std::{
 
 /// Walks through all selected files and calls a closure for each file.
 ///
-/// The closure `on_file` is called with two arguments:
+/// The closure `on_file` is called with three arguments:
 /// 1. The absolute path of the file.
 /// 2. The path of the file relative to the current working directory.
+/// 3. The view set on the selection entry the file was found through.
+///
+/// Lockfiles, generated and minified files found inside selected directories,
+/// as well as files that commonly hold credentials, are skipped according to
+/// `[files.exclude]` and reported on stderr.
 pub fn walk_selected_files<F>(selection: &Selection, mut on_file: F) -> Result<()>
 where
-    F: FnMut(&Path, &Path) -> Result<()>,
+    F: FnMut(&Path, &Path, Option<View>) -> Result<()>,
+{
+    walk_files(
+        selection,
+        &mut WorkingTree,
+        false,
+        |abs_path, rel_path, view, _| on_file(abs_path, rel_path, view),
+    )
+}
+
+/// Walks the selected files in the working tree, or at `rev` if given, and
+/// calls `on_file` with their content. See [`walk_selected_files`].
+///
+/// At `rev`, directories are walked with the same rules: hidden files and
+/// those matched by `.gitignore`, `.ignore` or `.selectignore` files at `rev`
+/// are left out. Named roots and crates outside the project aren't part of the
+/// repository, so their files are still read from disk.
+pub fn read_selected_files<F>(
+    selection: &Selection,
+    rev: Option<&str>,
+    mut on_file: F,
+) -> Result<()>
+where
+    F: FnMut(&Path, &Path, Option<View>, Vec<u8>) -> Result<()>,
 {
-    let git_root = git::repo_root()?;
+    let mut reader: Box<dyn ContentReader> = match rev {
+        Some(rev) => Box::new(RevTree::new(&root::project_root()?, rev)?),
+        None => Box::new(WorkingTree),
+    };
+    walk_files(
+        selection,
+        reader.as_mut(),
+        true,
+        |abs_path, rel_path, view, content| {
+            on_file(
+                abs_path,
+                rel_path,
+                view,
+                content.expect("content is read for every file"),
+            )
+        },
+    )
+}
+
+/// A file found through a selection entry.
+struct Candidate {
+    /// The absolute path of the file.
+    path: PathBuf,
+    /// Whether the file was found by walking a selected directory or through
+    /// git, as opposed to being selected directly.
+    walked: bool,
+    /// The view set on the entry.
+    view: Option<View>,
+}
+
+/// Lists and reads files, either in the working tree or in the tree of a git
+/// revision. The module graph of `+depth` entries is followed in the same tree.
+trait ContentReader: SourceTree {
+    /// The revision files are read at, or `None` for the working tree.
+    fn rev(&self) -> Option<&str>;
+
+    /// Adds the files that `selected_path`, an absolute path, stands for to
+    /// `files`.
+    fn walk(&self, selected_path: &SelectedPath, files: &mut Vec<Candidate>) -> Result<()>;
+
+    /// Gets ready to read the files among `paths`, where reading them in one
+    /// go is cheaper.
+    fn prefetch(&mut self, _paths: &[PathBuf]) -> Result<()> {
+        Ok(())
+    }
+
+    /// Whether the file exists.
+    fn exists(&self, path: &Path) -> bool;
+
+    /// Reads a file, or with `head_only`, at least its first bytes. Each file
+    /// is read at most once.
+    fn read(&mut self, path: &Path, head_only: bool) -> Result<Vec<u8>>;
+}
+
+/// Reads files from disk.
+struct WorkingTree;
+
+impl SourceTree for WorkingTree {
+    fn is_file(&self, path: &Path) -> bool {
+        path.is_file()
+    }
+
+    fn read_to_string(&self, path: &Path) -> Option<String> {
+        fs::read_to_string(path).ok()
+    }
+}
+
+impl ContentReader for WorkingTree {
+    fn rev(&self) -> Option<&str> {
+        None
+    }
+
+    fn walk(&self, selected_path: &SelectedPath, files: &mut Vec<Candidate>) -> Result<()> {
+        walk_path(selected_path, files)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        path.is_file()
+    }
+
+    fn read(&mut self, path: &Path, head_only: bool) -> Result<Vec<u8>> {
+        if head_only {
+            exclude::read_head(path)
+        } else {
+            read_file(path)
+        }
+    }
+}
+
+/// Reads files from the tree of a git revision.
+struct RevTree {
+    project_root: PathBuf,
+    rev: String,
+    /// The files in the tree, relative to the project root.
+    tree: BTreeSet<PathBuf>,
+    /// The ignore files in the tree, deepest first.
+    ignores: Vec<Gitignore>,
+    submodules: Vec<PathBuf>,
+    /// Prefetched contents by absolute path, `None` for files missing at `rev`.
+    blobs: HashMap<PathBuf, Option<Vec<u8>>>,
+}
+
+impl RevTree {
+    fn new(project_root: &Path, rev: &str) -> Result<Self> {
+        let tree = git::ls_tree(project_root, rev, Path::new(""), true)?
+            .into_iter()
+            .collect();
+        Ok(Self {
+            project_root: project_root.to_path_buf(),
+            rev: rev.to_owned(),
+            ignores: ignores_at(project_root, rev, &tree)?,
+            tree,
+            submodules: submodule_dirs(project_root)?,
+            blobs: HashMap::new(),
+        })
+    }
+}
+
+/// Files outside the project aren't in the repository, so like the files
+/// selected there, they are looked up on disk.
+impl SourceTree for RevTree {
+    fn is_file(&self, path: &Path) -> bool {
+        match path.strip_prefix(&self.project_root) {
+            Ok(repo_path) => self.tree.contains(repo_path),
+            Err(_) => path.is_file(),
+        }
+    }
+
+    fn read_to_string(&self, path: &Path) -> Option<String> {
+        let Ok(repo_path) = path.strip_prefix(&self.project_root) else {
+            return fs::read_to_string(path).ok();
+        };
+        let blob = git::read_blobs(&self.project_root, &self.rev, &[repo_path.to_path_buf()])
+            .ok()?
+            .pop()??;
+        String::from_utf8(blob).ok()
+    }
+}
+
+impl ContentReader for RevTree {
+    fn rev(&self) -> Option<&str> {
+        Some(&self.rev)
+    }
+
+    fn walk(&self, selected_path: &SelectedPath, files: &mut Vec<Candidate>) -> Result<()> {
+        let entry_path = selected_path
+            .path
+            .strip_prefix(&self.project_root)
+            .unwrap_or(&selected_path.path);
+        for path in git::ls_tree(
+            &self.project_root,
+            &self.rev,
+            entry_path,
+            selected_path.recursive,
+        )? {
+            let walked = path != entry_path;
+            let hidden = path
+                .strip_prefix(entry_path)
+                .unwrap_or(&path)
+                .components()
+                .any(|component| component.as_os_str().to_string_lossy().starts_with('.'));
+            let path = self.project_root.join(path);
+            if walked && (hidden || is_ignored(&self.ignores, &self.submodules, &path)) {
+                continue;
+            }
+            files.push(Candidate {
+                path,
+                walked,
+                view: selected_path.view,
+            });
+        }
+        Ok(())
+    }
+
+    fn prefetch(&mut self, paths: &[PathBuf]) -> Result<()> {
+        let repo_paths: Vec<_> = paths
+            .iter()
+            .map(|path| {
+                path.strip_prefix(&self.project_root)
+                    .unwrap_or(path)
+                    .to_path_buf()
+            })
+            .collect();
+        let contents = git::read_blobs(&self.project_root, &self.rev, &repo_paths)?;
+        self.blobs.extend(paths.iter().cloned().zip(contents));
+        Ok(())
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.blobs.get(path).is_some_and(Option::is_some)
+    }
+
+    fn read(&mut self, path: &Path, _head_only: bool) -> Result<Vec<u8>> {
+        self.blobs
+            .remove(path)
+            .flatten()
+            .ok_or_else(|| eyre!("{} is not in {}", path.display(), self.rev))
+    }
+}
+
+/// Does the work of [`walk_selected_files`] and [`read_selected_files`]:
+/// collects the files that the selection's entries stand for, leaves out those
+/// that `[files.exclude]` skips and calls `on_file` with the rest. Files inside
+/// the project are listed and read through `reader`, the others from disk.
+///
+/// With `read_content`, `on_file` is also given the content of each file,
+/// which is read only once, even when the exclusion heuristics look at it too.
+fn walk_files<F>(
+    selection: &Selection,
+    reader: &mut dyn ContentReader,
+    read_content: bool,
+    mut on_file: F,
+) -> Result<()>
+where
+    F: FnMut(&Path, &Path, Option<View>, Option<Vec<u8>>) -> Result<()>,
+{
+    let project_root = root::project_root()?;
     let current_dir = env::current_dir().wrap_err("failed to get current dir")?;
+    let config = Config::read()?;
+    let mut named_roots = NamedRoots::new(config.roots.as_ref())?;
+    let files_config = config.files.unwrap_or_default();
+    let mut disk = WorkingTree;
 
-    let selected_paths: Vec<SelectedPath> = selection
-        .clone()
-        .into_inner()
-        .into_iter()
-        .map(|p| SelectedPath::new(git_root.join(p.path), p.recursive))
+    let mut files = collect_files(
+        selection.clone().into_inner(),
+        reader,
+        &project_root,
+        &mut named_roots,
+    )?;
+
+    // Entries can overlap, e.g. a directory and `@changed`.
+    let mut seen = HashSet::new();
+    files.retain(|file| seen.insert(file.path.clone()));
+
+    let project_files: Vec<_> = files
+        .iter()
+        .filter(|file| is_in_project(&named_roots, &project_root, &file.path))
+        .map(|file| file.path.clone())
         .collect();
+    reader.prefetch(&project_files)?;
 
-    for selected_path in selected_paths {
-        let mut walk_builder = WalkBuilder::new(&selected_path.path);
-        walk_builder.add_custom_ignore_filename(CUSTOM_IGNORE_FILENAME);
-        if !selected_path.recursive {
-            // max_depth 1 means the root and its direct children.
-            // The root dir itself will be filtered out by `is_file()` check.
-            walk_builder.max_depth(Some(1));
+    // Files in named roots are checked relative to their root.
+    let repo_path = |path: &Path| match named_roots.locate(path) {
+        Some((_, path)) => path.to_path_buf(),
+        None => path
+            .strip_prefix(&project_root)
+            .unwrap_or(path)
+            .to_path_buf(),
+    };
+
+    let mut exclusions = Exclusions::new(&files_config.exclude)?;
+    let walked_repo_paths: Vec<_> = files
+        .iter()
+        .filter(|file| file.walked && is_in_project(&named_roots, &project_root, &file.path))
+        .map(|file| repo_path(&file.path))
+        .collect();
+    exclusions.load_attributes(&project_root, &walked_repo_paths, reader.rev())?;
+
+    let mut skipped = vec![];
+
+    for file in files {
+        let source: &mut dyn ContentReader =
+            if is_in_project(&named_roots, &project_root, &file.path) {
+                &mut *reader
+            } else {
+                &mut disk
+            };
+        // Files from git sources that are gone, or weren't there at the revision.
+        if !source.exists(&file.path) {
+            continue;
         }
 
-        for result in walk_builder.build() {
-            let item = result.wrap_err("failed to walk directories")?;
+        let relative_path = display_path(&named_roots, &current_dir, &file.path)?;
+
+        let repo_relative_path = repo_path(&file.path);
+        let mut content = None;
+        let reason = if file.walked {
+            exclusions.check(&repo_relative_path, || {
+                let bytes = source.read(&file.path, !read_content)?;
+                Ok(content.insert(bytes).as_slice())
+            })?
+        } else {
+            exclusions.check_sensitive(&repo_relative_path)
+        };
+        if let Some(reason) = reason {
+            skipped.push((relative_path, reason));
+            continue;
+        }
 
-            if let Some(file_type) = item.file_type() {
-                if file_type.is_file() {
-                    let absolute_path = item.path();
-                    let relative_path =
-                        diff_paths(absolute_path, &current_dir).ok_or_else(|| {
-                            eyre!(
-                                "failed to construct relative path for {}",
-                                absolute_path.display()
-                            )
-                        })?;
-
-                    on_file(absolute_path, &relative_path)?;
+        let content = match content {
+            _ if !read_content => None,
+            Some(content) => Some(content),
+            None => Some(source.read(&file.path, false)?),
+        };
+        on_file(&file.path, &relative_path, file.view, content)?;
+    }
+
+    report_skipped(&skipped);
+    Ok(())
+}
+
+/// Collects the files that `entries` stand for, with the modules that `+depth`
+/// entries use. Files inside the project are listed through `reader`, the
+/// others on disk, and crates outside the project are added to `named_roots`.
+fn collect_files(
+    entries: impl IntoIterator<Item = SelectedPath>,
+    reader: &dyn ContentReader,
+    project_root: &Path,
+    named_roots: &mut NamedRoots,
+) -> Result<Vec<Candidate>> {
+    let mut crates = Crates::new(project_root);
+    let mut files = vec![];
+    for entry in entries {
+        let entry = entry.in_roots(named_roots);
+        let start = files.len();
+        if let Some(source) = entry.git_source() {
+            if let Some(rev) = reader.rev()
+                && matches!(
+                    source,
+                    GitSource::Changed | GitSource::Staged | GitSource::Untracked
+                )
+            {
+                eprintln!(
+                    "Note: {} stands for no files at {rev}.",
+                    entry.path.display()
+                );
+            }
+            for path in git::source_files(project_root, &source, reader.rev())? {
+                // Untracked files include our own storage, unless it's ignored.
+                if !path.starts_with(STORAGE_DIRNAME) {
+                    files.push(Candidate {
+                        path: project_root.join(path),
+                        walked: true,
+                        view: entry.view,
+                    });
                 }
             }
+        } else if entry.crate_source().is_none() && entry.path.to_string_lossy().starts_with('@') {
+            return Err(eyre!(
+                "unknown selection entry '{}', expected @changed, @staged, @untracked, \
+                 @since:<rev>, @commit:<rev> or @crate:<name>",
+                entry.path.display()
+            ));
+        } else {
+            let path = match (entry.crate_source(), entry.named_root(named_roots)) {
+                (Some(source), _) => crates.resolve(&source, named_roots)?,
+                (None, Some((name, path))) => named_roots.resolve(name, path)?,
+                (None, None) => project_root.join(&entry.path),
+            };
+            let selected_path = SelectedPath::new(path, entry.recursive).with_view(entry.view);
+            if is_in_project(named_roots, project_root, &selected_path.path) {
+                reader.walk(&selected_path, &mut files)?;
+            } else {
+                WorkingTree.walk(&selected_path, &mut files)?;
+            }
+        }
+
+        if let Some(depth) = entry.expand {
+            let modules = module_files(reader, files[start..].iter().map(|file| &file.path), depth);
+            files.extend(modules.into_iter().map(|path| Candidate {
+                path,
+                walked: true,
+                view: entry.view,
+            }));
         }
     }
 
+    Ok(files)
+}
+
+/// Walks `selected_path` in the working tree and adds the files it finds to
+/// `files`.
+fn walk_path(selected_path: &SelectedPath, files: &mut Vec<Candidate>) -> Result<()> {
+    let mut walk_builder = WalkBuilder::new(&selected_path.path);
+    walk_builder.add_custom_ignore_filename(CUSTOM_IGNORE_FILENAME);
+    if !selected_path.recursive {
+        // max_depth 1 means the root and its direct children.
+        // The root dir itself will be filtered out by `is_file()` check.
+        walk_builder.max_depth(Some(1));
+    }
+
+    for result in walk_builder.build() {
+        let item = result.wrap_err("failed to walk directories")?;
+
+        if let Some(file_type) = item.file_type()
+            && file_type.is_file()
+        {
+            files.push(Candidate {
+                walked: item.depth() > 0,
+                path: item.into_path(),
+                view: selected_path.view,
+            });
+        }
+    }
     Ok(())
 }
 
+/// The files of the Rust modules that the Rust files among `paths` declare or
+/// use in `tree`, up to `depth` steps away.
+fn module_files<'a>(
+    tree: &dyn SourceTree,
+    paths: impl IntoIterator<Item = &'a PathBuf>,
+    depth: usize,
+) -> Vec<PathBuf> {
+    let seeds: Vec<_> = paths
+        .into_iter()
+        .filter(|path| path.extension().is_some_and(|extension| extension == "rs"))
+        .cloned()
+        .collect();
+    module_graph::expand(tree, &seeds, depth)
+}
+
+/// Resolves `@crate:name` entries, running `cargo metadata` on first use.
+struct Crates<'a> {
+    project_root: &'a Path,
+    metadata: Option<Metadata>,
+}
+
+impl<'a> Crates<'a> {
+    fn new(project_root: &'a Path) -> Self {
+        Self {
+            project_root,
+            metadata: None,
+        }
+    }
+
+    /// Returns the package that `source` stands for.
+    fn find(&mut self, source: &CrateSource) -> Result<&Package> {
+        if self.metadata.is_none() {
+            self.metadata = Some(Metadata::load(self.project_root)?);
+        }
+        let metadata = self.metadata.as_ref().expect("metadata was just loaded");
+        metadata.find(&source.name, source.version.as_deref())
+    }
+
+    /// Returns the absolute path that `source` stands for. A crate outside the
+    /// project is added to `named_roots`, so that its files are shown like
+    /// `@crate:clap@4.5.41/src/lib.rs`.
+    fn resolve(&mut self, source: &CrateSource, named_roots: &mut NamedRoots) -> Result<PathBuf> {
+        let project_root = self.project_root;
+        let package = self.find(source)?;
+        let dir = package.dir();
+        if !dir.starts_with(project_root) {
+            named_roots.add_crate(&package.label(), dir.to_path_buf());
+        }
+        Ok(dir.join(&source.path))
+    }
+}
+
+/// Whether `path` belongs to the project rather than to one of the named roots.
+fn is_in_project(named_roots: &NamedRoots, project_root: &Path, path: &Path) -> bool {
+    path.starts_with(project_root) && named_roots.locate(path).is_none()
+}
+
+/// Returns how `path` is shown: as `name:path` in a named root, and relative to
+/// the current directory otherwise.
+fn display_path(named_roots: &NamedRoots, current_dir: &Path, path: &Path) -> Result<PathBuf> {
+    if let Some(named_path) = named_roots.named_path(path) {
+        return Ok(named_path);
+    }
+    diff_paths(path, current_dir)
+        .ok_or_else(|| eyre!("failed to construct relative path for {}", path.display()))
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>> {
+    fs::read(path).wrap_err_with(|| format!("failed to read file {}", path.display()))
+}
+
+fn report_skipped(skipped: &[(PathBuf, Reason)]) {
+    if !skipped.is_empty() {
+        eprintln!("Skipped {} file(s):", skipped.len());
+        for (path, reason) in skipped {
+            eprintln!("  {} ({reason})", path.display());
+        }
+    }
+}
+
+/// Builds matchers for the ignore files among `tree`, the files in the tree of
+/// `rev`, deepest first.
+fn ignores_at(project_root: &Path, rev: &str, tree: &BTreeSet<PathBuf>) -> Result<Vec<Gitignore>> {
+    let names = [".gitignore", ".ignore", CUSTOM_IGNORE_FILENAME];
+    let mut paths: Vec<_> = tree
+        .iter()
+        .filter(|path| {
+            path.file_name()
+                .is_some_and(|name| names.iter().any(|ignore_name| name == *ignore_name))
+        })
+        .cloned()
+        .collect();
+    paths.sort_by_key(|path| std::cmp::Reverse(path.components().count()));
+    let contents = git::read_blobs(project_root, rev, &paths)?;
+
+    let mut ignores = vec![];
+    for (path, content) in paths.iter().zip(contents) {
+        let Some(content) = content else {
+            continue;
+        };
+        let dir = project_root.join(path.parent().unwrap_or(Path::new("")));
+        let mut builder = GitignoreBuilder::new(&dir);
+        for line in String::from_utf8_lossy(&content).lines() {
+            builder
+                .add_line(Some(project_root.join(path)), line)
+                .wrap_err_with(|| format!("invalid pattern in {}", path.display()))?;
+        }
+        ignores.push(
+            builder
+                .build()
+                .wrap_err_with(|| format!("failed to parse {}", path.display()))?,
+        );
+    }
+    Ok(ignores)
+}
+
+/// Whether the deepest ignore file with an opinion on `path` ignores it. Like
+/// git, ignore files outside the submodule that holds `path`, if any, are
+/// disregarded.
+fn is_ignored(ignores: &[Gitignore], submodules: &[PathBuf], path: &Path) -> bool {
+    let repository = submodules
+        .iter()
+        .filter(|submodule| path.starts_with(submodule))
+        .max_by_key(|submodule| submodule.components().count());
+    ignores
+        .iter()
+        .filter(|ignore| path.starts_with(ignore.path()))
+        .filter(|ignore| repository.is_none_or(|repository| ignore.path().starts_with(repository)))
+        .map(|ignore| ignore.matched_path_or_any_parents(path, false))
+        .find(|matched| !matched.is_none())
+        .is_some_and(|matched| matched.is_ignore())
+}
+
+/// Returns the checked-out submodules below `dir`, nested ones included, as
+/// absolute paths.
+fn submodule_dirs(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut dirs = vec![];
+    for submodule in git::submodules(dir)? {
+        let submodule = dir.join(submodule);
+        dirs.extend(submodule_dirs(&submodule)?);
+        dirs.push(submodule);
+    }
+    Ok(dirs)
+}
+
 /// Builds a string containing the contents of all selected files,
-/// formatted with `<file>` tags.
-pub fn get_selected_files_content_as_string(selection: &Selection) -> Result<String> {
+/// formatted with `<file>` tags and anonymized. Contents come from the git
+/// revision `rev` if given, and the working tree otherwise.
+pub fn get_selected_files_content_as_string(
+    selection: &Selection,
+    rev: Option<&str>,
+) -> Result<String> {
+    let project_root = root::project_root()?;
+    let config = Config::read()?;
+    let secrets_config = config.secrets.unwrap_or_default();
+    let mut processor = FileProcessor::new(config.files.unwrap_or_default(), &secrets_config)?;
+    // Uncommitted changes only make sense for the working tree of a repository.
+    let statuses = match rev {
+        None if git::is_repo(&project_root) => git::statuses(&project_root)?,
+        _ => HashMap::new(),
+    };
     let mut buf = String::new();
-    walk_selected_files(selection, |abs_path, rel_path| {
-        let content = fs::read_to_string(abs_path)
-            .wrap_err_with(|| format!("failed to read file {}", abs_path.display()))?;
+    read_selected_files(selection, rev, |abs_path, rel_path, view, bytes| {
+        let repo_path = abs_path.strip_prefix(&project_root).unwrap_or(abs_path);
+        let content = processor.process(repo_path, &bytes, view)?;
+        let status = statuses.get(repo_path).copied();
 
-        let error_message = "failed to write to buffer";
+        write_file_block(&mut buf, rel_path, &content, status, processor.config())
+    })?;
+    secrets::report(processor.findings(), secrets_config.action)?;
+    anonymize::apply(buf)
+}
 
-        write!(&mut buf, "<file path=\"{}\">\n", rel_path.display()).wrap_err(error_message)?;
+/// Appends a file to the buffer, wrapped in a `<file>` tag. Binary and
+/// oversized files are left out or replaced by an empty tag describing them,
+/// depending on the `[files]` config, and files without the requested view get
+/// an empty tag. Files with uncommitted changes get a `status` attribute.
+pub fn write_file_block(
+    buf: &mut String,
+    rel_path: &Path,
+    content: &FileContent,
+    status: Option<FileStatus>,
+    config: &FilesConfig,
+) -> Result<()> {
+    let error_message = "failed to write to buffer";
+    let status = status.map_or(String::new(), |status| format!(" status=\"{status}\""));
 
-        buf.push_str(&content);
+    match content {
+        FileContent::Text(text) => {
+            writeln!(buf, "<file path=\"{}\"{status}>", rel_path.display())
+                .wrap_err(error_message)?;
+            buf.push_str(text);
+            writeln!(buf, "</file>").wrap_err(error_message)
+        }
+        FileContent::Binary { size, kind } => match config.binary {
+            BinaryPolicy::Placeholder => writeln!(
+                buf,
+                "<file path=\"{}\"{status} binary=\"true\" size=\"{size}\" type=\"{kind}\" />",
+                rel_path.display()
+            )
+            .wrap_err(error_message),
+            BinaryPolicy::Skip => {
+                eprintln!("Skipped binary file {}.", rel_path.display());
+                Ok(())
+            }
+        },
+        FileContent::Oversized { size, tokens } => writeln!(
+            buf,
+            "<file path=\"{}\"{status} size=\"{size}\" tokens=\"{tokens}\" omitted=\"size limit\" />",
+            rel_path.display()
+        )
+        .wrap_err(error_message),
+        FileContent::Omitted { view } => writeln!(
+            buf,
+            "<file path=\"{}\"{status} omitted=\"no {view}\" />",
+            rel_path.display()
+        )
+        .wrap_err(error_message),
+    }
+}
 
-        write!(&mut buf, "</file>\n").wrap_err(error_message)?;
-        Ok(())
-    })?;
-    Ok(buf)
+/// Returns git pathspecs, relative to the repository root, that cover the
+/// selection. Git sources like `@changed` are resolved to their files, as of
+/// `rev` if given, and crates to their directories, and what is outside the
+/// project is left out.
+pub fn selection_pathspecs(
+    project_root: &Path,
+    selection: &Selection,
+    rev: Option<&str>,
+) -> Result<Vec<String>> {
+    let mut named_roots = NamedRoots::new(Config::read()?.roots.as_ref())?;
+    let mut crates = Crates::new(project_root);
+    let mut pathspecs = vec![];
+    for entry in selection.clone().into_inner() {
+        let entry = entry.in_roots(&named_roots);
+        if let Some(source) = entry.git_source() {
+            let files = git::source_files(project_root, &source, rev)?;
+            pathspecs.extend(files.iter().map(|path| path.display().to_string()));
+            continue;
+        }
+        // Named roots and crates outside the project aren't in the repository.
+        let path = match (entry.crate_source(), entry.named_root(&named_roots)) {
+            (Some(source), _) => {
+                let path = crates.find(&source)?.dir().join(&source.path);
+                match path.strip_prefix(project_root) {
+                    Ok(path) => path.to_path_buf(),
+                    Err(_) => continue,
+                }
+            }
+            (None, Some(_)) => continue,
+            (None, None) => entry.path,
+        };
+
+        let path = match path.display().to_string() {
+            path if path.is_empty() => ".".to_owned(),
+            path => path,
+        };
+        if entry.recursive {
+            pathspecs.push(path);
+        } else {
+            pathspecs.push(format!(":(glob){path}/*"));
+        }
+    }
+
+    // The modules that `+depth` entries use needn't be under their paths.
+    let expanded: Vec<_> = selection
+        .clone()
+        .into_inner()
+        .into_iter()
+        .filter(|entry| entry.expand.is_some())
+        .collect();
+    if !expanded.is_empty() {
+        let reader: Box<dyn ContentReader> = match rev {
+            Some(rev) => Box::new(RevTree::new(project_root, rev)?),
+            None => Box::new(WorkingTree),
+        };
+        for file in collect_files(expanded, reader.as_ref(), project_root, &mut named_roots)? {
+            if is_in_project(&named_roots, project_root, &file.path)
+                && let Ok(path) = file.path.strip_prefix(project_root)
+            {
+                pathspecs.push(format!(":(literal){}", path.display()));
+            }
+        }
+    }
+
+    pathspecs.sort_unstable();
+    pathspecs.dedup();
+    Ok(pathspecs)
+}
+
+/// Returns pathspecs that leave out the files among `paths`, relative to the
+/// repository root, that rendering the selection would skip, so what git
+/// shows about them doesn't bypass `[files.exclude]`. Files that are gone from
+/// the working tree are only checked by name.
+pub fn exclude_pathspecs(
+    project_root: &Path,
+    selection: &Selection,
+    paths: &[PathBuf],
+) -> Result<Vec<String>> {
+    let config = Config::read()?;
+    let named_roots = NamedRoots::new(config.roots.as_ref())?;
+    let mut exclusions = Exclusions::new(&config.files.unwrap_or_default().exclude)?;
+    exclusions.load_attributes(project_root, paths, None)?;
+
+    // Like in the walks, only files found in selected directories or through
+    // git are subject to more than the credential check.
+    let direct: HashSet<_> = selection
+        .clone()
+        .into_inner()
+        .into_iter()
+        .map(|entry| entry.in_roots(&named_roots))
+        .filter(|entry| !entry.is_dynamic() && entry.named_root(&named_roots).is_none())
+        .map(|entry| entry.path)
+        .collect();
+
+    let mut pathspecs = vec![];
+    for path in paths {
+        let reason = if direct.contains(path) {
+            exclusions.check_sensitive(path)
+        } else {
+            let abs_path = project_root.join(path);
+            let mut head = None;
+            exclusions.check(path, || {
+                let bytes = if abs_path.is_file() {
+                    exclude::read_head(&abs_path)?
+                } else {
+                    vec![]
+                };
+                Ok(head.insert(bytes).as_slice())
+            })?
+        };
+        if reason.is_some() {
+            pathspecs.push(format!(":(exclude,literal){}", path.display()));
+        }
+    }
+    Ok(pathspecs)
 }
 
 /// Builds a string containing the paths of all selected files, one per line.
 pub fn get_selected_files_paths_as_string(selection: &Selection) -> Result<String> {
     let mut buf = String::new();
-    walk_selected_files(selection, |_abs_path, rel_path| {
+    walk_selected_files(selection, |_abs_path, rel_path, _view| {
         writeln!(&mut buf, "{}", rel_path.display()).wrap_err("failed to write to buffer")
     })?;
     Ok(buf)
@@ -102,15 +818,34 @@ pub fn get_global_selection() -> Result<Selection> {
 pub fn interactive_selection(
     roots: &[PathBuf],
     previous_selection: Option<Selection>,
-    git_root: &PathBuf,
+    project_root: &PathBuf,
 ) -> Result<Selection> {
     // 1. Load existing selections from config, making paths absolute. This is our starting point.
-    let mut final_paths: HashMap<PathBuf, bool> = previous_selection
+    // Entries like `@changed` or `@crate:clap` are kept as they are.
+    let named_roots = NamedRoots::new(Config::read()?.roots.as_ref())?;
+    let (mut dynamic_entries, previous_paths): (Vec<_>, Vec<_>) = previous_selection
         .unwrap_or_default()
         .into_inner()
         .into_iter()
-        .map(|sp| (git_root.join(sp.path), sp.recursive))
-        .collect();
+        .map(|sp| sp.in_roots(&named_roots))
+        .partition(SelectedPath::is_dynamic);
+    dynamic_entries.sort_unstable();
+    let absolute_path = |sp: &SelectedPath| match sp.named_root(&named_roots) {
+        Some((name, path)) => named_roots.resolve(name, path),
+        None => Ok(project_root.join(&sp.path)),
+    };
+    let views: HashMap<PathBuf, View> = previous_paths
+        .iter()
+        .filter_map(|sp| sp.view.map(|view| Ok((absolute_path(sp)?, view))))
+        .collect::<Result<_>>()?;
+    let expands: HashMap<PathBuf, usize> = previous_paths
+        .iter()
+        .filter_map(|sp| sp.expand.map(|depth| Ok((absolute_path(sp)?, depth))))
+        .collect::<Result<_>>()?;
+    let mut final_paths: HashMap<PathBuf, bool> = previous_paths
+        .iter()
+        .map(|sp| Ok((absolute_path(sp)?, sp.recursive)))
+        .collect::<Result<_>>()?;
 
     // Keep track of what was in the config to decide which paths are "new" suggestions.
     let originally_selected_paths: HashSet<PathBuf> = final_paths.keys().cloned().collect();
@@ -143,25 +878,55 @@ pub fn interactive_selection(
         for result in walk_builder.build() {
             let item = result.wrap_err("failed to walk directories")?;
 
-            if let Some(file_type) = item.file_type() {
-                if file_type.is_dir() {
-                    // For discovered sub-directories, only add them if they are not
-                    // already in our selection map. This preserves any `recursive: false`
-                    // setting on existing selections.
-                    final_paths.entry(item.into_path()).or_insert(true);
-                }
+            if let Some(file_type) = item.file_type()
+                && file_type.is_dir()
+            {
+                // For discovered sub-directories, only add them if they are not
+                // already in our selection map. This preserves any `recursive: false`
+                // setting on existing selections.
+                final_paths.entry(item.into_path()).or_insert(true);
             }
         }
     }
 
-    if final_paths.is_empty() {
+    // 4. List the files of the Rust modules that entries with a `+depth`
+    // suffix use, unless a recursive entry already covers them. They are only
+    // shown: the entries add them when rendering, following the modules as
+    // they change.
+    let mut expanded = BTreeSet::new();
+    for sp in &previous_paths {
+        let Some(depth) = sp.expand else {
+            continue;
+        };
+        let mut files = vec![];
+        walk_path(
+            &SelectedPath::new(absolute_path(sp)?, sp.recursive),
+            &mut files,
+        )?;
+        for path in module_files(&WorkingTree, files.iter().map(|file| &file.path), depth) {
+            let covered = final_paths
+                .iter()
+                .any(|(selected, recursive)| *recursive && path.starts_with(selected));
+            if !covered {
+                expanded.insert(path);
+            }
+        }
+    }
+
+    if final_paths.is_empty() && dynamic_entries.is_empty() {
         return Ok(Selection::default());
     }
 
-    // 4. Prepare the buffer for the editor.
+    // 5. Prepare the buffer for the editor.
     let all_paths_vec: Vec<_> = final_paths
         .into_iter()
-        .map(|(path, recursive)| SelectedPath::new(path, recursive))
+        .map(|(path, recursive)| {
+            let view = views.get(&path).copied();
+            let expand = expands.get(&path).copied();
+            SelectedPath::new(path, recursive)
+                .with_view(view)
+                .with_expand(expand)
+        })
         .collect();
 
     let (mut selected_paths, mut new_suggested_paths): (Vec<_>, Vec<_>) = all_paths_vec
@@ -175,26 +940,34 @@ pub fn interactive_selection(
 
     const HEADER: &str = "# Lines starting with '#' are ignored.\n\
                           # To select a path recursively, use its name: path/to/dir\n\
-                          # To select a path non-recursively (only files in the directory), prefix with '*': *path/to/dir\n\n";
+                          # To select a path non-recursively (only files in the directory), prefix with '*': *path/to/dir\n\
+                          # To show only the signatures of Rust files, suffix with ':skeleton': path/to/dir:skeleton\n\
+                          # To show only an outline of declarations, suffix with ':outline': path/to/dir:outline\n\
+                          # To also select the Rust modules a file uses, up to N steps away, suffix with '+N': path/to/file.rs+1\n\
+                          # To select files through git, use @changed, @staged, @untracked, @since:<rev> or @commit:<rev>\n\
+                          # To select a path in a root from [roots] in select.toml, prefix with its name: deps:serde/src\n\
+                          # To select the sources of a crate from the Cargo workspace, use @crate:<name>, e.g. @crate:serde/src\n\n";
 
     let mut buf = String::from(HEADER);
 
     let to_relative_string = |path_item: &SelectedPath| -> Result<String> {
-        let relative_path = diff_paths(&path_item.path, &current_dir).ok_or_else(|| {
-            eyre!(
-                "failed to construct relative path for {}",
-                path_item.path.display()
-            )
-        })?;
-        let path_to_write = SelectedPath::new(relative_path, path_item.recursive);
+        let relative_path = display_path(&named_roots, &current_dir, &path_item.path)?;
+        let path_to_write = SelectedPath::new(relative_path, path_item.recursive)
+            .with_view(path_item.view)
+            .with_expand(path_item.expand);
         Ok(path_to_write.to_string())
     };
 
+    for entry in &dynamic_entries {
+        writeln!(&mut buf, "{entry}").unwrap();
+    }
+
     for path_item in &selected_paths {
         writeln!(&mut buf, "{}", to_relative_string(path_item)?).unwrap();
     }
 
-    if !selected_paths.is_empty() && !new_suggested_paths.is_empty() {
+    let has_selected = !selected_paths.is_empty() || !dynamic_entries.is_empty();
+    if has_selected && !new_suggested_paths.is_empty() {
         buf.push('\n');
     }
 
@@ -202,10 +975,18 @@ pub fn interactive_selection(
         writeln!(&mut buf, "# {}", to_relative_string(path_item)?).unwrap();
     }
 
+    if !expanded.is_empty() {
+        buf.push_str("\n# Also selected when rendering, through the '+N' entries above:\n");
+        for path in &expanded {
+            let relative_path = display_path(&named_roots, &current_dir, path)?;
+            writeln!(&mut buf, "#   {}", relative_path.display()).unwrap();
+        }
+    }
+
     let cursor_line = HEADER.lines().count() + 1;
     let result = editor::get_user_input_from_file_content(&buf, cursor_line, None)?;
 
-    // 5. Parse the user's final selection from the editor buffer.
+    // 6. Parse the user's final selection from the editor buffer.
     let mut paths = HashSet::new();
     let mut errors = Vec::new();
 
@@ -214,9 +995,24 @@ pub fn interactive_selection(
         .filter(|line| !line.starts_with('#') && !line.trim().is_empty())
         .map(|line| {
             let trimmed_line = line.trim();
-            let selected_path_relative: SelectedPath = trimmed_line.parse().unwrap();
-            fs::canonicalize(&selected_path_relative.path)
-                .map(|canonical| SelectedPath::new(canonical, selected_path_relative.recursive))
+            let selected_path_relative = trimmed_line
+                .parse::<SelectedPath>()
+                .unwrap()
+                .in_roots(&named_roots);
+            if selected_path_relative.is_dynamic() {
+                return Ok(selected_path_relative);
+            }
+            // Other paths are relative to the current directory, as written.
+            let path = match selected_path_relative.named_root(&named_roots) {
+                Some((name, path)) => named_roots.resolve(name, path)?,
+                None => selected_path_relative.path.clone(),
+            };
+            fs::canonicalize(&path)
+                .map(|canonical| {
+                    SelectedPath::new(canonical, selected_path_relative.recursive)
+                        .with_view(selected_path_relative.view)
+                        .with_expand(selected_path_relative.expand)
+                })
                 .wrap_err_with(|| {
                     format!(
                         "failed to canonicalize {}",
@@ -240,11 +1036,20 @@ pub fn interactive_selection(
         let relative_paths = paths
             .into_iter()
             .map(|p| {
-                diff_paths(&p.path, git_root)
+                if p.is_dynamic() {
+                    return Ok(p);
+                }
+                named_roots
+                    .named_path(&p.path)
+                    .or_else(|| diff_paths(&p.path, project_root))
                     .ok_or_else(|| {
                         eyre!("failed to construct relative path for {}", p.path.display())
                     })
-                    .map(|relative| SelectedPath::new(relative, p.recursive))
+                    .map(|relative| {
+                        SelectedPath::new(relative, p.recursive)
+                            .with_view(p.view)
+                            .with_expand(p.expand)
+                    })
             })
             .collect::<Result<HashSet<_>>>()
             .wrap_err("failed to convert absolute paths to relative paths")?;
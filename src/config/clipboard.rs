@@ -0,0 +1,61 @@
+// MIT License
+//
+// Copyright (c) 2025 Dmytro Prokhorov
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+use serde::{Deserialize, Serialize};
+
+/// How the clipboard sink hands the prompt over to the system clipboard.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ClipboardMode {
+    /// `background` when a display server is available, `osc52` otherwise.
+    /// Falls back to `osc52` if the system clipboard can't be reached.
+    #[default]
+    Auto,
+    /// Hand the contents to a detached process that owns the selection until
+    /// another application takes it. Only relevant on X11/Wayland.
+    Background,
+    /// Block until another application takes ownership of the selection.
+    Wait,
+    /// Set the contents and exit right away. On X11/Wayland the contents are
+    /// lost unless a clipboard manager picks them up.
+    Immediate,
+    /// Ask the terminal to set the clipboard with an OSC 52 escape sequence.
+    Osc52,
+}
+
+/// Which selection to write to. `primary` is the X11/Wayland PRIMARY
+/// selection (middle-click paste) and is treated as `clipboard` elsewhere.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ClipboardTarget {
+    #[default]
+    Clipboard,
+    Primary,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ClipboardConfig {
+    #[serde(default)]
+    pub mode: ClipboardMode,
+    #[serde(default)]
+    pub target: ClipboardTarget,
+}
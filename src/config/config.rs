@@ -20,8 +20,8 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
 // THE SOFTWARE.
 
-use crate::config::Selection;
-use crate::git;
+use crate::config::{ClipboardConfig, FilesConfig, RootsConfig, SecretsConfig, Selection};
+use crate::root;
 
 use std::{fs, io, path::PathBuf};
 
@@ -32,14 +32,14 @@ use serde::{Deserialize, Serialize};
 #[derive(Default, Deserialize, Serialize)]
 pub struct Config {
     pub selection: Option<Selection>,
+    pub clipboard: Option<ClipboardConfig>,
+    pub files: Option<FilesConfig>,
+    pub secrets: Option<SecretsConfig>,
+    pub roots: Option<RootsConfig>,
 }
 
 fn file_path() -> Result<PathBuf> {
-    let file_path = git::repo_root()?
-        //
-        .join(".select")
-        //
-        .join("select.toml");
+    let file_path = root::storage_dir()?.join("select.toml");
 
     Ok(file_path)
 }
@@ -0,0 +1,213 @@
+// MIT License
+//
+// Copyright (c) 2025 Dmytro Prokhorov
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+use crate::config::selection::View;
+
+use serde::{Deserialize, Serialize};
+
+/// What to do with selected files that contain binary data.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BinaryPolicy {
+    /// Emit an empty `<file>` entry with the file's size and type.
+    #[default]
+    Placeholder,
+    /// Leave the file out and print a notice.
+    Skip,
+}
+
+/// How to decode text files that aren't valid UTF-8. A UTF-16 byte order mark
+/// is honored either way.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EncodingStrategy {
+    /// Guess the legacy encoding from the content, e.g. `windows-1252` or
+    /// `shift_jis`.
+    #[default]
+    Detect,
+    /// Decode with `fallback_encoding`.
+    Fallback,
+    /// Decode as UTF-8, replacing invalid sequences with U+FFFD.
+    Lossy,
+}
+
+/// What to do with a file that exceeds the limits of a [`LimitRule`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OversizeAction {
+    /// Emit an empty `<file>` entry with the file's size and token count.
+    #[default]
+    Placeholder,
+    /// Keep the first `head` and last `tail` lines with an elision marker in between.
+    Truncate,
+}
+
+/// Caps the size of files whose repository-relative path matches `glob`.
+/// A rule without `max_bytes` and `max_tokens` applies to every matching file.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct LimitRule {
+    pub glob: String,
+    pub max_bytes: Option<u64>,
+    pub max_tokens: Option<usize>,
+    #[serde(default)]
+    pub action: OversizeAction,
+    #[serde(default = "default_head")]
+    pub head: usize,
+    #[serde(default = "default_tail")]
+    pub tail: usize,
+}
+
+fn default_head() -> usize {
+    100
+}
+
+fn default_tail() -> usize {
+    20
+}
+
+/// Automatic exclusion of lockfiles, generated and minified files that are
+/// picked up through a selected directory. Files selected by their own path are
+/// only excluded if they look like they hold credentials.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ExcludeConfig {
+    /// Leave out files that commonly hold credentials, such as `.env` files
+    /// and private keys, even when selected directly.
+    #[serde(default = "default_true")]
+    pub sensitive: bool,
+    /// Use the built-in list of lockfiles and generated file patterns.
+    #[serde(default = "default_true")]
+    pub builtin: bool,
+    /// Look for generated-file markers, very long lines and the
+    /// `linguist-generated` attribute in `.gitattributes`.
+    #[serde(default = "default_true")]
+    pub heuristics: bool,
+    /// Lines longer than this mark a file as minified.
+    #[serde(default = "default_max_line_length")]
+    pub max_line_length: usize,
+    /// Additional globs to exclude.
+    #[serde(default)]
+    pub globs: Vec<String>,
+    /// Globs that are never excluded automatically, e.g. `**/Cargo.lock`.
+    #[serde(default)]
+    pub allow: Vec<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_max_line_length() -> usize {
+    1000
+}
+
+impl Default for ExcludeConfig {
+    fn default() -> Self {
+        Self {
+            sensitive: true,
+            builtin: true,
+            heuristics: true,
+            max_line_length: default_max_line_length(),
+            globs: Vec::new(),
+            allow: Vec::new(),
+        }
+    }
+}
+
+/// A step in a per-file transformation pipeline.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Transform {
+    /// Remove comments in languages the lexer understands.
+    StripComments,
+    /// Replace runs of blank lines with a single one and drop those at the
+    /// start and end.
+    CollapseBlankLines,
+    TrimTrailingWhitespace,
+    /// Convert CRLF line endings to LF.
+    NormalizeCrlf,
+    /// Prefix every line with its line number.
+    LineNumbers,
+    /// Redact secrets, regardless of `secrets.action`.
+    Redact,
+    /// Keep the first `head` and last `tail` lines.
+    Truncate {
+        head: usize,
+        tail: usize,
+    },
+}
+
+/// Transforms applied, in order, to text files whose repository-relative path
+/// matches `glob`, e.g. `steps = ["normalize-crlf", "line-numbers"]`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TransformRule {
+    pub glob: String,
+    pub steps: Vec<Transform>,
+}
+
+/// Presents files whose repository-relative path matches `glob` with `view`,
+/// unless their selection entry sets a view of its own.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ViewRule {
+    pub glob: String,
+    pub view: View,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct FilesConfig {
+    #[serde(default)]
+    pub binary: BinaryPolicy,
+    #[serde(default)]
+    pub encoding: EncodingStrategy,
+    /// A WHATWG encoding label, e.g. `windows-1252` or `shift_jis`, for
+    /// `encoding = "fallback"`.
+    #[serde(default = "default_fallback_encoding")]
+    pub fallback_encoding: String,
+    /// The first matching rule applies.
+    #[serde(default)]
+    pub limits: Vec<LimitRule>,
+    #[serde(default)]
+    pub exclude: ExcludeConfig,
+    /// The first matching rule applies.
+    #[serde(default)]
+    pub transforms: Vec<TransformRule>,
+    /// The first matching rule applies.
+    #[serde(default)]
+    pub views: Vec<ViewRule>,
+}
+
+fn default_fallback_encoding() -> String {
+    "windows-1252".to_owned()
+}
+
+impl Default for FilesConfig {
+    fn default() -> Self {
+        Self {
+            binary: BinaryPolicy::default(),
+            encoding: EncodingStrategy::default(),
+            fallback_encoding: default_fallback_encoding(),
+            limits: Vec::new(),
+            exclude: ExcludeConfig::default(),
+            transforms: Vec::new(),
+            views: Vec::new(),
+        }
+    }
+}
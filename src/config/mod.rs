@@ -20,10 +20,27 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
 // THE SOFTWARE.
 
+pub mod clipboard;
+
+#[allow(clippy::module_inception)]
 pub mod config;
 
+pub mod files;
+
+pub mod roots;
+
+pub mod secrets;
+
 pub mod selection;
 
+pub use clipboard::ClipboardConfig;
+
 pub use config::Config;
 
+pub use files::FilesConfig;
+
+pub use roots::RootsConfig;
+
+pub use secrets::SecretsConfig;
+
 pub use selection::Selection;
@@ -20,14 +20,22 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
 // THE SOFTWARE.
 
-use arboard::Clipboard;
-use color_eyre::eyre::{Result, WrapErr};
+use std::collections::BTreeMap;
 
-pub fn copy_to_clipboard(content: String) -> Result<()> {
-    let mut clipboard = Clipboard::new().wrap_err("failed to initialize clipboard")?;
-    clipboard
-        .set_text(content)
-        .wrap_err("failed to copy to clipboard")?;
-    eprintln!("Copied to clipboard.");
-    Ok(())
-}
+use serde::{Deserialize, Serialize};
+
+/// Named directories outside the project, such as a sibling repository or a
+/// vendored crate, that selections can refer to as `name:path`, e.g.
+/// `deps:serde/src`:
+///
+/// ```toml
+/// [roots]
+/// deps = "~/.cargo/registry/src/index.crates.io-1949cf8c6b5b557f"
+/// ```
+///
+/// A leading `~` stands for the home directory and relative paths are taken
+/// from the project root. The whole root is `deps:`, so with a view it is
+/// `deps::outline`, while `deps:outline` is the directory `outline` in it.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct RootsConfig(pub BTreeMap<String, String>);
@@ -0,0 +1,85 @@
+// MIT License
+//
+// Copyright (c) 2025 Dmytro Prokhorov
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+use serde::{Deserialize, Serialize};
+
+/// What to do when emitted file contents look like they contain secrets.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SecretsAction {
+    /// Replace each match with a `[REDACTED:<kind>]` marker and report it.
+    #[default]
+    Redact,
+    /// Refuse to render and report what was found.
+    Block,
+    /// Don't scan.
+    Off,
+}
+
+/// A custom secret pattern. Matches of the first capture group are redacted,
+/// or of the whole regex if it has no groups.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SecretPattern {
+    pub name: String,
+    pub regex: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SecretsConfig {
+    #[serde(default)]
+    pub action: SecretsAction,
+    /// Also flag long random-looking strings that no pattern recognizes.
+    #[serde(default = "default_entropy")]
+    pub entropy: bool,
+    /// Shannon entropy, in bits per character, above which a string is flagged.
+    #[serde(default = "default_min_entropy")]
+    pub min_entropy: f64,
+    /// Strings shorter than this are never flagged for entropy.
+    #[serde(default = "default_min_length")]
+    pub min_length: usize,
+    #[serde(default)]
+    pub patterns: Vec<SecretPattern>,
+}
+
+fn default_entropy() -> bool {
+    true
+}
+
+fn default_min_entropy() -> f64 {
+    4.5
+}
+
+fn default_min_length() -> usize {
+    32
+}
+
+impl Default for SecretsConfig {
+    fn default() -> Self {
+        Self {
+            action: SecretsAction::default(),
+            entropy: default_entropy(),
+            min_entropy: default_min_entropy(),
+            min_length: default_min_length(),
+            patterns: Vec::new(),
+        }
+    }
+}
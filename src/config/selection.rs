@@ -20,19 +20,195 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
 // THE SOFTWARE.
 
-use std::{collections::HashSet, path::PathBuf, str::FromStr};
+use crate::root::NamedRoots;
+
+use std::{
+    collections::HashSet,
+    fmt,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
+/// How the files of a selection entry are presented.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum View {
+    /// The whole file.
+    Full,
+    /// Rust items with function bodies replaced by `{ ... }`. Other files are
+    /// only listed.
+    Skeleton,
+    /// Declarations and their nesting, with line numbers. Files in languages
+    /// without an outline are only listed.
+    Outline,
+}
+
+impl FromStr for View {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "full" => Ok(Self::Full),
+            "skeleton" => Ok(Self::Skeleton),
+            "outline" => Ok(Self::Outline),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for View {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Full => write!(f, "full"),
+            Self::Skeleton => write!(f, "skeleton"),
+            Self::Outline => write!(f, "outline"),
+        }
+    }
+}
+
+/// A selection entry that is resolved through git at render time, such as
+/// `@changed` or `@since:main`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum GitSource {
+    /// Files that differ between the working tree and `HEAD`.
+    Changed,
+    /// Files with staged changes.
+    Staged,
+    /// Untracked files that aren't ignored.
+    Untracked,
+    /// Files changed since the merge base with a revision, including
+    /// uncommitted changes.
+    Since(String),
+    /// Files changed by a commit.
+    Commit(String),
+}
+
+impl FromStr for GitSource {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let source = s.strip_prefix('@').ok_or(())?;
+        match source.split_once(':') {
+            None => match source {
+                "changed" => Ok(Self::Changed),
+                "staged" => Ok(Self::Staged),
+                "untracked" => Ok(Self::Untracked),
+                _ => Err(()),
+            },
+            // A revision can't start with `-`, which git would take for an option.
+            Some(("since", rev)) if is_rev(rev) => Ok(Self::Since(rev.to_owned())),
+            Some(("commit", rev)) if is_rev(rev) => Ok(Self::Commit(rev.to_owned())),
+            Some(_) => Err(()),
+        }
+    }
+}
+
+fn is_rev(rev: &str) -> bool {
+    !rev.is_empty() && !rev.starts_with('-')
+}
+
+/// A selection entry for the sources of a crate in the Cargo workspace or its
+/// dependencies, such as `@crate:clap` or `@crate:serde@1.0.219/src`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CrateSource {
+    pub name: String,
+    pub version: Option<String>,
+    /// The path within the crate, empty for all of it.
+    pub path: PathBuf,
+}
+
+impl FromStr for CrateSource {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let source = s.strip_prefix("@crate:").ok_or(())?;
+        let (spec, path) = source.split_once('/').unwrap_or((source, ""));
+        let (name, version) = match spec.split_once('@') {
+            Some((name, version)) if !version.is_empty() => (name, Some(version.to_owned())),
+            Some(_) => return Err(()),
+            None => (spec, None),
+        };
+        if name.is_empty() {
+            return Err(());
+        }
+
+        Ok(Self {
+            name: name.to_owned(),
+            version,
+            path: PathBuf::from(path),
+        })
+    }
+}
+
 #[derive(Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
 pub struct SelectedPath {
     pub path: PathBuf,
     pub recursive: bool,
+    /// Set with a `:view` suffix, e.g. `src/net:skeleton`. Falls back to
+    /// `[[files.views]]` when unset.
+    pub view: Option<View>,
+    /// Set with a `+depth` suffix, e.g. `src/cli.rs+2`, to also select the
+    /// Rust modules the entry's files declare or use, up to `depth` steps away.
+    pub expand: Option<usize>,
 }
 
 impl SelectedPath {
     pub fn new(path: PathBuf, recursive: bool) -> Self {
-        Self { path, recursive }
+        Self {
+            path,
+            recursive,
+            view: None,
+            expand: None,
+        }
+    }
+
+    pub fn with_view(mut self, view: Option<View>) -> Self {
+        self.view = view;
+        self
+    }
+
+    pub fn with_expand(mut self, expand: Option<usize>) -> Self {
+        self.expand = expand;
+        self
+    }
+
+    /// The git source this entry stands for, if its path is one like `@changed`.
+    pub fn git_source(&self) -> Option<GitSource> {
+        self.path.to_str()?.parse().ok()
+    }
+
+    /// The crate this entry stands for, if its path is one like `@crate:clap`.
+    pub fn crate_source(&self) -> Option<CrateSource> {
+        self.path.to_str()?.parse().ok()
+    }
+
+    /// Whether this entry is resolved when it's walked, like `@changed` or
+    /// `@crate:clap`, rather than naming a path.
+    pub fn is_dynamic(&self) -> bool {
+        self.path.to_string_lossy().starts_with('@')
+    }
+
+    /// The name of the root in `[roots]` and the path within it, if this entry
+    /// is one like `deps:serde/src` and `deps` is one of `roots`. Other paths
+    /// with a colon, e.g. `notes:v2`, are plain paths.
+    pub fn named_root(&self, roots: &NamedRoots) -> Option<(&str, &Path)> {
+        let (name, path) = self.path.to_str()?.split_once(':')?;
+        roots.contains(name).then_some((name, Path::new(path)))
+    }
+
+    /// Reads an entry like `deps:outline`, which parses as `deps` with the
+    /// outline view, as the directory `outline` in the root `deps` if `deps` is
+    /// one of `roots`. The whole root with a view is written `deps::outline`.
+    pub fn in_roots(mut self, roots: &NamedRoots) -> Self {
+        if let Some(view) = self.view
+            && self.path.to_str().is_some_and(|name| roots.contains(name))
+        {
+            self.path = PathBuf::from(format!("{}:{view}", self.path.display()));
+            self.view = None;
+        }
+        self
     }
 }
 
@@ -40,23 +216,46 @@ impl FromStr for SelectedPath {
     type Err = std::convert::Infallible;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (s, view) = match s.rsplit_once(':') {
+            Some((path, view)) => match view.parse() {
+                Ok(view) => (path, Some(view)),
+                Err(()) => (s, None),
+            },
+            None => (s, None),
+        };
+        let (s, expand) = match s.rsplit_once('+') {
+            Some((path, depth)) => match depth.parse() {
+                Ok(depth) => (path, Some(depth)),
+                Err(_) => (s, None),
+            },
+            None => (s, None),
+        };
+
         let recursive = !s.starts_with('*');
         let path_str = if recursive { s } else { &s[1..] };
 
         Ok(Self {
             path: PathBuf::from(path_str),
             recursive,
+            view,
+            expand,
         })
     }
 }
 
-impl ToString for SelectedPath {
-    fn to_string(&self) -> String {
-        if self.recursive {
-            self.path.display().to_string()
-        } else {
-            format!("*{}", self.path.display())
+impl fmt::Display for SelectedPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.recursive {
+            write!(f, "*")?;
+        }
+        write!(f, "{}", self.path.display())?;
+        if let Some(depth) = self.expand {
+            write!(f, "+{depth}")?;
+        }
+        if let Some(view) = self.view {
+            write!(f, ":{view}")?;
         }
+        Ok(())
     }
 }
 
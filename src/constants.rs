@@ -20,4 +20,15 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
 // THE SOFTWARE.
 
-pub const CUSTOM_IGNORE_FILENAME: &'static str = ".selectignore";
+pub const CUSTOM_IGNORE_FILENAME: &str = ".selectignore";
+
+/// The directory, in the project root, that holds the configuration, features,
+/// templates and render history.
+pub const STORAGE_DIRNAME: &str = ".select";
+
+/// Environment variable that sets the project root, like `--root`.
+pub const ROOT_ENV_VAR: &str = "SELECT_ROOT";
+
+/// Git configuration that, when false, gives each linked worktree its own
+/// `.select/` rather than sharing the one of the main worktree.
+pub const SHARE_STORAGE_CONFIG_KEY: &str = "select.shareStorage";
@@ -0,0 +1,495 @@
+// MIT License
+//
+// Copyright (c) 2025 Dmytro Prokhorov
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+use std::path::Path;
+
+/// A language whose comments the lexer understands.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Language {
+    Rust,
+    Go,
+    JavaScript,
+    /// C, C++, Java, C#, Kotlin, Swift and friends.
+    CLike,
+    Python,
+    Toml,
+    Shell,
+    Yaml,
+}
+
+impl Language {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension().and_then(|e| e.to_str())?;
+        let language = match extension {
+            "rs" => Language::Rust,
+            "go" => Language::Go,
+            "js" | "mjs" | "cjs" | "jsx" | "ts" | "mts" | "cts" | "tsx" => Language::JavaScript,
+            "c" | "h" | "cc" | "cpp" | "cxx" | "hpp" | "hh" | "java" | "cs" | "kt" | "kts"
+            | "swift" | "scala" | "dart" | "proto" => Language::CLike,
+            "py" | "pyi" => Language::Python,
+            "toml" => Language::Toml,
+            "sh" | "bash" | "zsh" => Language::Shell,
+            "yml" | "yaml" => Language::Yaml,
+            _ => return None,
+        };
+        Some(language)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SegmentKind {
+    Code,
+    String,
+    Comment,
+}
+
+/// A slice of the source. Line comments don't include the line break.
+#[derive(Clone, Copy, Debug)]
+pub struct Segment<'a> {
+    pub kind: SegmentKind,
+    pub text: &'a str,
+}
+
+/// Splits `text` into code, string literal and comment segments, so comments
+/// can be removed without breaking strings that happen to contain `//` or `#`.
+pub fn segments(text: &str, language: Language) -> Vec<Segment<'_>> {
+    let mut lexer = Lexer {
+        text,
+        bytes: text.as_bytes(),
+        language,
+        pos: 0,
+        code_start: 0,
+        segments: vec![],
+    };
+    lexer.run();
+    lexer.segments
+}
+
+struct Lexer<'a> {
+    text: &'a str,
+    bytes: &'a [u8],
+    language: Language,
+    pos: usize,
+    /// Start of the code segment that is being accumulated.
+    code_start: usize,
+    segments: Vec<Segment<'a>>,
+}
+
+impl<'a> Lexer<'a> {
+    fn run(&mut self) {
+        while self.pos < self.bytes.len() {
+            if let Some(end) = self.comment_end() {
+                self.push(SegmentKind::Comment, end);
+            } else if let Some(end) = self.string_end() {
+                self.push(SegmentKind::String, end);
+            } else {
+                self.pos += 1;
+            }
+        }
+        self.flush_code();
+    }
+
+    fn rest(&self) -> &'a [u8] {
+        &self.bytes[self.pos..]
+    }
+
+    fn starts_with(&self, prefix: &str) -> bool {
+        self.rest().starts_with(prefix.as_bytes())
+    }
+
+    fn flush_code(&mut self) {
+        if self.code_start < self.pos {
+            self.segments.push(Segment {
+                kind: SegmentKind::Code,
+                text: &self.text[self.code_start..self.pos],
+            });
+        }
+    }
+
+    fn push(&mut self, kind: SegmentKind, end: usize) {
+        // Segments always end on a char boundary: every delimiter is ASCII.
+        let end = end.min(self.bytes.len());
+        self.flush_code();
+        self.segments.push(Segment {
+            kind,
+            text: &self.text[self.pos..end],
+        });
+        self.pos = end;
+        self.code_start = end;
+    }
+
+    /// Whether the previous byte ends a word, for languages where `#` only
+    /// starts a comment at the beginning of a word.
+    fn at_word_start(&self) -> bool {
+        self.pos == 0
+            || matches!(
+                self.bytes[self.pos - 1],
+                b' ' | b'\t' | b'\n' | b'\r' | b';'
+            )
+    }
+
+    fn line_end(&self) -> usize {
+        self.find_from(self.pos, "\n").unwrap_or(self.bytes.len())
+    }
+
+    fn find_from(&self, from: usize, needle: &str) -> Option<usize> {
+        self.text
+            .get(from..)
+            .and_then(|rest| rest.find(needle))
+            .map(|i| from + i)
+    }
+
+    fn comment_end(&self) -> Option<usize> {
+        // `#!` on the first line is a shebang, not a comment to strip.
+        if self.pos == 0 && self.starts_with("#!") {
+            return None;
+        }
+        match self.language {
+            Language::Rust | Language::Go | Language::JavaScript | Language::CLike => {
+                if self.starts_with("//") {
+                    Some(self.line_end())
+                } else if self.starts_with("/*") {
+                    Some(self.block_comment_end(self.language == Language::Rust))
+                } else {
+                    None
+                }
+            }
+            Language::Python | Language::Toml => self.starts_with("#").then(|| self.line_end()),
+            Language::Shell | Language::Yaml => {
+                (self.starts_with("#") && self.at_word_start()).then(|| self.line_end())
+            }
+        }
+    }
+
+    fn block_comment_end(&self, nested: bool) -> usize {
+        let mut depth = 0;
+        let mut i = self.pos;
+        while i < self.bytes.len() {
+            if self.bytes[i..].starts_with(b"/*") {
+                depth += 1;
+                i += 2;
+            } else if self.bytes[i..].starts_with(b"*/") {
+                depth -= 1;
+                i += 2;
+                if depth == 0 || !nested {
+                    return i;
+                }
+            } else {
+                i += 1;
+            }
+        }
+        self.bytes.len()
+    }
+
+    fn string_end(&self) -> Option<usize> {
+        let byte = self.bytes[self.pos];
+        match self.language {
+            Language::Rust => match byte {
+                b'r' | b'b' if self.is_word_boundary_before() => self.rust_raw_string_end(),
+                b'"' => Some(self.quoted_end(self.pos + 1, b'"', true)),
+                b'\'' => self.char_literal_end(self.pos),
+                _ => None,
+            },
+            Language::Go => match byte {
+                b'"' => Some(self.quoted_end(self.pos + 1, b'"', true)),
+                b'`' => Some(self.quoted_end(self.pos + 1, b'`', false)),
+                b'\'' => self.char_literal_end(self.pos),
+                _ => None,
+            },
+            Language::JavaScript => match byte {
+                b'"' | b'\'' | b'`' => Some(self.quoted_end(self.pos + 1, byte, true)),
+                b'/' if self.regex_allowed() => self.regex_end(),
+                _ => None,
+            },
+            Language::CLike => match byte {
+                b'"' => Some(self.quoted_end(self.pos + 1, b'"', true)),
+                b'\'' => self.char_literal_end(self.pos),
+                _ => None,
+            },
+            Language::Python | Language::Toml => match byte {
+                b'"' | b'\'' => Some(self.python_string_end(byte)),
+                _ => None,
+            },
+            Language::Shell => match byte {
+                b'\'' => Some(self.quoted_end(self.pos + 1, b'\'', false)),
+                b'"' => Some(self.quoted_end(self.pos + 1, b'"', true)),
+                b'<' if self.starts_with("<<") && !self.starts_with("<<<") => self.heredoc_end(),
+                _ => None,
+            },
+            Language::Yaml => match byte {
+                b'\'' | b'"' if self.at_word_start() || self.after_yaml_indicator() => {
+                    Some(self.quoted_end(self.pos + 1, byte, byte == b'"'))
+                }
+                _ => None,
+            },
+        }
+    }
+
+    fn is_word_boundary_before(&self) -> bool {
+        self.pos == 0 || {
+            let prev = self.bytes[self.pos - 1];
+            !(prev.is_ascii_alphanumeric() || prev == b'_')
+        }
+    }
+
+    /// Whether a `/` here starts a JavaScript regex literal rather than being
+    /// a division, going by the token before it: a regex can follow an
+    /// operator, an opening bracket or a keyword like `return`, but not a value.
+    fn regex_allowed(&self) -> bool {
+        const KEYWORDS: &[&str] = &[
+            "return",
+            "typeof",
+            "instanceof",
+            "in",
+            "of",
+            "new",
+            "delete",
+            "void",
+            "throw",
+            "case",
+            "do",
+            "else",
+            "yield",
+            "await",
+        ];
+
+        let before = self.text[..self.pos].trim_end();
+        let Some(prev) = before.bytes().last() else {
+            return true;
+        };
+        if prev.is_ascii_alphanumeric() || prev == b'_' || prev == b'$' {
+            let word_start = before
+                .rfind(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '$'))
+                .map_or(0, |i| i + 1);
+            return KEYWORDS.contains(&&before[word_start..]);
+        }
+        !matches!(prev, b')' | b']' | b'"' | b'\'' | b'`')
+    }
+
+    /// End of a regex literal like `/https?:\/\//g`, including its flags, if
+    /// one starts here. Regexes can't span lines.
+    fn regex_end(&self) -> Option<usize> {
+        let mut i = self.pos + 1;
+        let mut in_class = false;
+        while i < self.bytes.len() {
+            match self.bytes[i] {
+                b'\n' => return None,
+                b'\\' => i += 1,
+                b'[' => in_class = true,
+                b']' => in_class = false,
+                b'/' if !in_class => {
+                    let flags = self.bytes[i + 1..]
+                        .iter()
+                        .take_while(|b| b.is_ascii_alphabetic())
+                        .count();
+                    return Some(i + 1 + flags);
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+        None
+    }
+
+    fn after_yaml_indicator(&self) -> bool {
+        self.pos > 0 && matches!(self.bytes[self.pos - 1], b':' | b'-' | b'[' | b'{' | b',')
+    }
+
+    /// End of a string that started before `from` and is closed by `quote`.
+    fn quoted_end(&self, from: usize, quote: u8, escapes: bool) -> usize {
+        let mut i = from;
+        while i < self.bytes.len() {
+            match self.bytes[i] {
+                b'\\' if escapes => i += 2,
+                b if b == quote => return i + 1,
+                _ => i += 1,
+            }
+        }
+        self.bytes.len()
+    }
+
+    /// `'x'`, `'\n'` or `'\u{1F600}'`; anything else, such as a Rust lifetime,
+    /// is not a literal.
+    fn char_literal_end(&self, start: usize) -> Option<usize> {
+        let rest = &self.text[start + 1..];
+        let mut chars = rest.char_indices();
+        match chars.next()? {
+            (_, '\\') => {
+                let close = rest[1..].find('\'')?;
+                // Escapes are short; a distant quote belongs to something else.
+                (close <= 10).then_some(start + 1 + 1 + close + 1)
+            }
+            (_, '\'' | '\n') => None,
+            (_, c) => {
+                let after = c.len_utf8();
+                rest[after..]
+                    .starts_with('\'')
+                    .then_some(start + 1 + after + 1)
+            }
+        }
+    }
+
+    /// `r"..."`, `r#"..."#`, `b"..."`, `br#"..."#` and `b'x'`.
+    fn rust_raw_string_end(&self) -> Option<usize> {
+        let rest = self.rest();
+        let mut i = 0;
+        if rest.first() == Some(&b'b') {
+            i += 1;
+            match rest.get(i) {
+                Some(b'"') => return Some(self.quoted_end(self.pos + i + 1, b'"', true)),
+                Some(b'\'') => return self.char_literal_end(self.pos + i),
+                _ => {}
+            }
+        }
+        if rest.get(i) != Some(&b'r') {
+            return None;
+        }
+        i += 1;
+        let hashes = rest[i..].iter().take_while(|&&b| b == b'#').count();
+        i += hashes;
+        if rest.get(i) != Some(&b'"') {
+            return None;
+        }
+        let closing = format!("\"{}", "#".repeat(hashes));
+        let end = self
+            .find_from(self.pos + i + 1, &closing)
+            .map_or(self.bytes.len(), |start| start + closing.len());
+        Some(end)
+    }
+
+    /// Single, double and triple-quoted strings.
+    fn python_string_end(&self, quote: u8) -> usize {
+        let triple = [quote; 3];
+        // TOML literal strings ('...') have no escapes.
+        let escapes = !(self.language == Language::Toml && quote == b'\'');
+        if self.rest().starts_with(&triple) {
+            let mut i = self.pos + 3;
+            while i < self.bytes.len() {
+                if escapes && self.bytes[i] == b'\\' {
+                    i += 2;
+                } else if self.bytes[i..].starts_with(&triple) {
+                    return i + 3;
+                } else {
+                    i += 1;
+                }
+            }
+            self.bytes.len()
+        } else {
+            self.quoted_end(self.pos + 1, quote, escapes)
+        }
+    }
+
+    /// A here-document, from `<<` to the line holding only the delimiter.
+    fn heredoc_end(&self) -> Option<usize> {
+        let header_end = self.line_end();
+        let header = &self.text[self.pos + 2..header_end];
+        let header = header.strip_prefix('-').unwrap_or(header).trim_start();
+        let delimiter: String = header
+            .trim_start_matches(['\'', '"'])
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric() || *c == '_')
+            .collect();
+        if delimiter.is_empty() {
+            return None;
+        }
+
+        let mut line_start = header_end + 1;
+        while line_start < self.bytes.len() {
+            let line_end = self.find_from(line_start, "\n").unwrap_or(self.bytes.len());
+            if self.text[line_start..line_end].trim() == delimiter {
+                return Some(line_end);
+            }
+            line_start = line_end + 1;
+        }
+        Some(self.bytes.len())
+    }
+}
+
+/// Removes comments, dropping lines that held nothing but a comment and
+/// trailing whitespace left in front of removed comments.
+pub fn strip_comments(text: &str, language: Language) -> String {
+    let segments = segments(text, language);
+    remove_segments(&segments, |i| segments[i].kind == SegmentKind::Comment)
+}
+
+/// Reassembles `segments`, leaving out those whose index `remove` returns
+/// `true` for. Lines left empty by a removal are dropped, as is trailing
+/// whitespace in front of a removed segment.
+pub fn remove_segments(segments: &[Segment<'_>], remove: impl Fn(usize) -> bool) -> String {
+    let mut output = String::new();
+    let mut line = String::new();
+    let mut removed_on_line = false;
+
+    for (i, segment) in segments.iter().enumerate() {
+        match segment.kind {
+            _ if remove(i) => removed_on_line = true,
+            SegmentKind::Code => {
+                let mut parts = segment.text.split('\n');
+                line.push_str(parts.next().unwrap_or_default());
+                for part in parts {
+                    finish_line(&mut line, &mut removed_on_line, &mut output);
+                    line.push_str(part);
+                }
+            }
+            // Line breaks inside strings and comments are content, so the
+            // lines they end are never dropped.
+            SegmentKind::String | SegmentKind::Comment => {
+                let mut parts = segment.text.split('\n');
+                line.push_str(parts.next().unwrap_or_default());
+                for part in parts {
+                    output.push_str(&line);
+                    output.push('\n');
+                    line.clear();
+                    removed_on_line = false;
+                    line.push_str(part);
+                }
+            }
+        }
+    }
+
+    if !line.is_empty() {
+        if removed_on_line {
+            output.push_str(line.trim_end());
+        } else {
+            output.push_str(&line);
+        }
+    }
+
+    output
+}
+
+/// Ends the current output line. A line that had a comment removed loses its
+/// trailing whitespace, and is dropped entirely if nothing else is left.
+fn finish_line(line: &mut String, removed: &mut bool, output: &mut String) {
+    if *removed {
+        let trimmed = line.trim_end();
+        if !trimmed.is_empty() {
+            output.push_str(trimmed);
+            output.push('\n');
+        }
+    } else {
+        output.push_str(line);
+        output.push('\n');
+    }
+    line.clear();
+    *removed = false;
+}
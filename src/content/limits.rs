@@ -0,0 +1,107 @@
+// MIT License
+//
+// Copyright (c) 2025 Dmytro Prokhorov
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+use crate::{
+    config::files::{LimitRule, OversizeAction},
+    content::FileContent,
+    token::{self, TokenCount},
+};
+
+use std::path::Path;
+
+use color_eyre::eyre::{Result, WrapErr};
+use globset::{Glob, GlobMatcher};
+
+/// The size limits from `[[files.limits]]`, with their globs compiled.
+pub struct Limits {
+    rules: Vec<(GlobMatcher, LimitRule)>,
+}
+
+impl Limits {
+    pub fn new(rules: &[LimitRule]) -> Result<Self> {
+        let rules = rules
+            .iter()
+            .map(|rule| {
+                let glob = Glob::new(&rule.glob)
+                    .wrap_err_with(|| format!("invalid glob '{}' in files.limits", rule.glob))?;
+                Ok((glob.compile_matcher(), rule.clone()))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { rules })
+    }
+
+    /// Applies the first rule matching `path` to the content of a text file.
+    pub fn apply(&self, path: &Path, text: String) -> FileContent {
+        let Some((_, rule)) = self.rules.iter().find(|(glob, _)| glob.is_match(path)) else {
+            return FileContent::Text(text);
+        };
+
+        let unlimited = rule.max_bytes.is_none() && rule.max_tokens.is_none();
+        if !unlimited && within_limits(rule, &text) {
+            return FileContent::Text(text);
+        }
+
+        if rule.action == OversizeAction::Truncate {
+            let truncated = truncate_lines(&text, rule.head, rule.tail);
+            // Truncating by lines doesn't help with a few very long lines.
+            if unlimited || within_limits(rule, &truncated) {
+                return FileContent::Text(truncated);
+            }
+        }
+
+        FileContent::Oversized {
+            size: text.len() as u64,
+            tokens: token::estimate(&text),
+        }
+    }
+}
+
+fn within_limits(rule: &LimitRule, text: &str) -> bool {
+    let bytes_ok = rule.max_bytes.is_none_or(|max| text.len() as u64 <= max);
+    let tokens_ok = rule
+        .max_tokens
+        .is_none_or(|max| token::estimate(text) <= TokenCount(max));
+    bytes_ok && tokens_ok
+}
+
+/// Keeps the first `head` and the last `tail` lines, replacing the rest with a marker.
+pub fn truncate_lines(text: &str, head: usize, tail: usize) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    if lines.len() <= head + tail {
+        return text.to_owned();
+    }
+
+    let omitted = lines.len() - head - tail;
+    let marker = format!("... [{omitted} lines omitted] ...");
+    let mut result = lines[..head]
+        .iter()
+        .copied()
+        .chain([marker.as_str()])
+        .chain(lines[lines.len() - tail..].iter().copied())
+        .collect::<Vec<_>>()
+        .join("\n");
+    // Only the omitted lines differ from the original.
+    if text.ends_with('\n') {
+        result.push('\n');
+    }
+    result
+}
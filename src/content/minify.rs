@@ -0,0 +1,107 @@
+// MIT License
+//
+// Copyright (c) 2025 Dmytro Prokhorov
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+use crate::content::{
+    lexer::{self, Language, Segment, SegmentKind},
+    transform,
+};
+
+/// Removes comments and redundant blank lines. Doc comments, and Python
+/// docstrings, are kept when `keep_docs` is set.
+pub fn minify(text: &str, language: Language, keep_docs: bool) -> String {
+    let segments = lexer::segments(text, language);
+
+    let stripped = lexer::remove_segments(&segments, |i| {
+        let segment = &segments[i];
+        match segment.kind {
+            SegmentKind::Comment => !(keep_docs && is_doc_comment(&segments, i, language)),
+            SegmentKind::String => !keep_docs && is_docstring(&segments, i, language),
+            SegmentKind::Code => false,
+        }
+    });
+
+    transform::trim_trailing_whitespace(&transform::collapse_blank_lines(&stripped))
+}
+
+fn is_doc_comment(segments: &[Segment<'_>], i: usize, language: Language) -> bool {
+    let text = segments[i].text;
+    let javadoc = text.starts_with("/**") && !text.starts_with("/***") && text != "/**/";
+    match language {
+        Language::Rust => {
+            (text.starts_with("///") && !text.starts_with("////"))
+                || text.starts_with("//!")
+                || text.starts_with("/*!")
+                || javadoc
+        }
+        Language::JavaScript | Language::CLike => javadoc,
+        // Go doc comments are ordinary comments right above a declaration.
+        Language::Go => next_code_line(segments, i).is_some_and(|line| {
+            ["package ", "func ", "type ", "var ", "const "]
+                .iter()
+                .any(|keyword| line.starts_with(keyword))
+        }),
+        Language::Python | Language::Toml | Language::Shell | Language::Yaml => false,
+    }
+}
+
+/// A triple-quoted Python string that stands on its own lines.
+fn is_docstring(segments: &[Segment<'_>], i: usize, language: Language) -> bool {
+    let text = segments[i].text;
+    if language != Language::Python || !(text.starts_with("\"\"\"") || text.starts_with("'''")) {
+        return false;
+    }
+
+    let alone_before = i == 0 || {
+        let previous = segments[i - 1];
+        previous.kind == SegmentKind::Code
+            && previous
+                .text
+                .rsplit('\n')
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .is_empty()
+            && (i == 1 || previous.text.contains('\n'))
+    };
+    let alone_after = segments.get(i + 1).is_none_or(|next| {
+        next.kind == SegmentKind::Code
+            && next
+                .text
+                .split('\n')
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .is_empty()
+    });
+
+    alone_before && alone_after
+}
+
+/// The first line of code after segment `i`, skipping comments and blank lines.
+fn next_code_line<'a>(segments: &[Segment<'a>], i: usize) -> Option<&'a str> {
+    segments[i + 1..]
+        .iter()
+        .filter(|segment| segment.kind != SegmentKind::Comment)
+        .flat_map(|segment| segment.text.split('\n'))
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+}
@@ -0,0 +1,340 @@
+// MIT License
+//
+// Copyright (c) 2025 Dmytro Prokhorov
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+pub mod lexer;
+
+pub mod limits;
+
+pub mod minify;
+
+pub mod outline;
+
+pub mod skeleton;
+
+pub mod transform;
+
+pub mod view;
+
+use crate::{
+    config::{
+        SecretsConfig,
+        files::{EncodingStrategy, FilesConfig},
+        secrets::SecretsAction,
+        selection::View,
+    },
+    secrets::{Finding, Scanner},
+    token::{self, TokenCount},
+};
+use lexer::Language;
+use limits::Limits;
+use transform::Transforms;
+use view::Views;
+
+use std::path::{Path, PathBuf};
+
+use chardetng::EncodingDetector;
+use color_eyre::eyre::{Result, eyre};
+use encoding_rs::{Encoding, UTF_8};
+
+/// Bytes inspected when deciding whether a file is binary, as git does.
+const SNIFF_LEN: usize = 8000;
+
+/// The content of a selected file, as it should be presented to the model.
+pub enum FileContent {
+    Text(String),
+    Binary {
+        size: u64,
+        kind: &'static str,
+    },
+    /// A text file left out because it exceeds a `[[files.limits]]` rule.
+    Oversized {
+        size: u64,
+        tokens: TokenCount,
+    },
+    /// A text file left out because its view has nothing to show for its
+    /// language, e.g. the outline of a Markdown file.
+    Omitted {
+        view: View,
+    },
+}
+
+/// Turns the raw bytes of selected files into what gets emitted, according to
+/// the `[files]` and `[secrets]` config.
+pub struct FileProcessor {
+    config: FilesConfig,
+    limits: Limits,
+    transforms: Transforms,
+    views: Views,
+    scanner: Option<Scanner>,
+    scan_all: bool,
+    findings: Vec<Finding>,
+    minify: Option<Minify>,
+}
+
+/// Settings and running totals for `--minify`.
+struct Minify {
+    keep_docs: bool,
+    tokens_before: TokenCount,
+    tokens_after: TokenCount,
+}
+
+impl FileProcessor {
+    pub fn new(config: FilesConfig, secrets: &SecretsConfig) -> Result<Self> {
+        let limits = Limits::new(&config.limits)?;
+        let transforms = Transforms::new(&config.transforms)?;
+        let views = Views::new(&config.views)?;
+        let scan_all = secrets.action != SecretsAction::Off;
+        let scanner = if scan_all || transforms.redacts() {
+            Some(Scanner::new(secrets)?)
+        } else {
+            None
+        };
+        Ok(Self {
+            config,
+            limits,
+            transforms,
+            views,
+            scanner,
+            scan_all,
+            findings: vec![],
+            minify: None,
+        })
+    }
+
+    /// Shows `focus` paths, relative to the repository root, in full whatever
+    /// their view.
+    pub fn with_focus(mut self, focus: Vec<PathBuf>) -> Self {
+        self.views.set_focus(focus);
+        self
+    }
+
+    /// Strips comments and redundant blank lines from files in a known
+    /// language, keeping doc comments if `keep_docs` is set.
+    pub fn with_minify(mut self, keep_docs: bool) -> Self {
+        self.minify = Some(Minify {
+            keep_docs,
+            tokens_before: TokenCount::default(),
+            tokens_after: TokenCount::default(),
+        });
+        self
+    }
+
+    pub fn config(&self) -> &FilesConfig {
+        &self.config
+    }
+
+    /// Processes a file, where `path` is relative to the repository root and
+    /// `view` is the one set on the selection entry the file was found through.
+    pub fn process(
+        &mut self,
+        path: &Path,
+        bytes: &[u8],
+        view: Option<View>,
+    ) -> Result<FileContent> {
+        let text = match decode(path, bytes, &self.config)? {
+            FileContent::Text(text) => text,
+            other => return Ok(other),
+        };
+
+        // Files without a skeleton or outline are only listed, so a map of a
+        // large tree doesn't fill up with READMEs and config files.
+        let text = match self.views.resolve(path, view) {
+            View::Full => text,
+            View::Skeleton => match Language::from_path(path) {
+                Some(Language::Rust) => skeleton::rust(&text).unwrap_or_else(|| {
+                    eprintln!(
+                        "Failed to parse {}; including it in full instead of as a skeleton.",
+                        path.display()
+                    );
+                    text
+                }),
+                _ => {
+                    return Ok(FileContent::Omitted {
+                        view: View::Skeleton,
+                    });
+                }
+            },
+            View::Outline => match Language::from_path(path)
+                .and_then(|language| outline::outline(&text, language))
+            {
+                Some(outline) => outline,
+                None => {
+                    return Ok(FileContent::Omitted {
+                        view: View::Outline,
+                    });
+                }
+            },
+        };
+
+        // Before the transforms, so steps like `line-numbers` see the lines
+        // that are actually emitted.
+        let text = match (&mut self.minify, Language::from_path(path)) {
+            (Some(minify), Some(language)) => {
+                let minified = minify::minify(&text, language, minify.keep_docs);
+                minify.tokens_before.0 += token::estimate(&text).0;
+                minify.tokens_after.0 += token::estimate(&minified).0;
+                minified
+            }
+            _ => text,
+        };
+
+        let text = self.scan(path, text);
+
+        let text = self
+            .transforms
+            .apply(path, text, self.scanner.as_ref(), &mut self.findings);
+
+        Ok(self.limits.apply(path, text))
+    }
+
+    /// Redacts secrets in other text emitted for the file at `path`, such as
+    /// its uncommitted hunks, unless scanning is off.
+    pub fn scan(&mut self, path: &Path, text: String) -> String {
+        match self.scanner {
+            Some(ref scanner) if self.scan_all => {
+                let (redacted, findings) = scanner.redact(path, &text);
+                self.findings.extend(findings);
+                redacted
+            }
+            _ => text,
+        }
+    }
+
+    /// The potential secrets redacted so far.
+    pub fn findings(&self) -> &[Finding] {
+        &self.findings
+    }
+
+    /// Estimated tokens of the minified files before and after minifying, if
+    /// `--minify` is on.
+    pub fn minified_tokens(&self) -> Option<(TokenCount, TokenCount)> {
+        self.minify
+            .as_ref()
+            .map(|minify| (minify.tokens_before, minify.tokens_after))
+    }
+}
+
+/// Decodes the raw bytes of a file, detecting binary content and non-UTF-8 text.
+pub fn decode(path: &Path, bytes: &[u8], config: &FilesConfig) -> Result<FileContent> {
+    // A byte order mark means text, even though UTF-16 is full of NUL bytes.
+    if let Some((encoding, bom_len)) = Encoding::for_bom(bytes) {
+        let (text, _) = encoding.decode_without_bom_handling(&bytes[bom_len..]);
+        return Ok(FileContent::Text(text.into_owned()));
+    }
+
+    let sniffed = &bytes[..bytes.len().min(SNIFF_LEN)];
+    if sniffed.contains(&0) {
+        return Ok(FileContent::Binary {
+            size: bytes.len() as u64,
+            kind: binary_kind(path, bytes),
+        });
+    }
+
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return Ok(FileContent::Text(text.to_owned()));
+    }
+
+    let encoding = match config.encoding {
+        EncodingStrategy::Lossy => UTF_8,
+        EncodingStrategy::Detect => {
+            let mut detector = EncodingDetector::new();
+            detector.feed(bytes, true);
+            detector.guess(None, false)
+        }
+        EncodingStrategy::Fallback => Encoding::for_label(config.fallback_encoding.as_bytes())
+            .ok_or_else(|| {
+                eyre!(
+                    "unknown fallback encoding '{}' in select.toml",
+                    config.fallback_encoding
+                )
+            })?,
+    };
+    let (text, _) = encoding.decode_without_bom_handling(bytes);
+    Ok(FileContent::Text(text.into_owned()))
+}
+
+/// Guesses a media type from well-known magic numbers, then from the extension.
+fn binary_kind(path: &Path, bytes: &[u8]) -> &'static str {
+    const MAGIC: &[(&[u8], &str)] = &[
+        (b"\x89PNG\r\n\x1a\n", "image/png"),
+        (b"\xff\xd8\xff", "image/jpeg"),
+        (b"GIF8", "image/gif"),
+        (b"%PDF", "application/pdf"),
+        (b"PK\x03\x04", "application/zip"),
+        (b"\x1f\x8b", "application/gzip"),
+        (b"\0asm", "application/wasm"),
+        (b"\x7fELF", "application/x-elf"),
+        (b"SQLite format 3\0", "application/vnd.sqlite3"),
+    ];
+
+    if let Some((_, kind)) = MAGIC.iter().find(|(magic, _)| bytes.starts_with(magic)) {
+        return kind;
+    }
+
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("ico") => "image/x-icon",
+        Some("webp") => "image/webp",
+        Some("woff" | "woff2" | "ttf" | "otf") => "font",
+        Some("exe" | "dll" | "so" | "dylib" | "a" | "o" | "rlib") => "application/x-executable",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(path: &str, text: &str, view: View) -> FileContent {
+        let mut processor =
+            FileProcessor::new(FilesConfig::default(), &SecretsConfig::default()).unwrap();
+        processor
+            .process(Path::new(path), text.as_bytes(), Some(view))
+            .unwrap()
+    }
+
+    #[test]
+    fn files_without_an_outline_are_omitted() {
+        assert!(matches!(
+            process("README.md", "# Title\n\nSome prose.\n", View::Outline),
+            FileContent::Omitted {
+                view: View::Outline
+            }
+        ));
+        assert!(matches!(
+            process("config.toml", "key = 1\n", View::Skeleton),
+            FileContent::Omitted {
+                view: View::Skeleton
+            }
+        ));
+    }
+
+    #[test]
+    fn supported_files_get_an_outline() {
+        let FileContent::Text(outline) =
+            process("lib.rs", "pub fn run() {\n    work();\n}\n", View::Outline)
+        else {
+            panic!("expected an outline");
+        };
+        assert!(outline.contains("pub fn run()"), "{outline}");
+        assert!(!outline.contains("work()"), "{outline}");
+    }
+}
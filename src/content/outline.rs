@@ -0,0 +1,253 @@
+// MIT License
+//
+// Copyright (c) 2025 Dmytro Prokhorov
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+use crate::content::lexer::{self, Language, SegmentKind};
+
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+/// Declarations that can be listed, as patterns matched against a line with
+/// its indentation, strings and comments removed. The `kind` group names the
+/// keyword, and kinds in `containers` have their members listed too. Members
+/// match either pattern, while top-level declarations only match
+/// `declarations`.
+struct Grammar {
+    declarations: Vec<Regex>,
+    members: Vec<Regex>,
+    containers: &'static [&'static str],
+}
+
+/// Statements that look like declarations to the patterns below.
+const CONTROL_KEYWORDS: &[&str] = &[
+    "return", "new", "else", "throw", "case", "if", "while", "for", "switch", "do", "delete",
+    "goto", "sizeof", "await", "yield",
+];
+
+fn grammar(language: Language) -> Option<&'static Grammar> {
+    static RUST: LazyLock<Grammar> = LazyLock::new(|| Grammar {
+        declarations: vec![regex(
+            r"^(?:pub(?:\([^)]*\))?\s+)?(?:(?:const|async|unsafe|extern|default)\s+)*(?P<kind>fn|struct|enum|union|trait|impl|mod|type|const|static|macro_rules!)[\s<{(!]",
+        )],
+        members: vec![],
+        containers: &["impl", "trait", "mod"],
+    });
+    static GO: LazyLock<Grammar> = LazyLock::new(|| Grammar {
+        declarations: vec![
+            regex(r"^type\s+\w+(?:\[[^\]]*\])?\s+(?P<kind>interface)\b"),
+            regex(r"^(?P<kind>func|type|var|const|package)\b"),
+        ],
+        // Methods of interfaces.
+        members: vec![regex(r"^\w+\(")],
+        containers: &["interface"],
+    });
+    static JAVASCRIPT: LazyLock<Grammar> = LazyLock::new(|| Grammar {
+        declarations: vec![regex(
+            r"^(?:export\s+)?(?:default\s+)?(?:declare\s+)?(?:abstract\s+)?(?:async\s+)?(?P<kind>function\*?|class|interface|type|enum|namespace|module|const|let|var)[\s*]",
+        )],
+        members: vec![regex(
+            r"^(?:(?:public|private|protected|static|async|get|set|readonly|override|abstract)\s+)*(?:\*\s*)?[#\w$]+\s*[<(]",
+        )],
+        containers: &["class", "interface", "namespace", "module"],
+    });
+    static C_LIKE: LazyLock<Grammar> = LazyLock::new(|| Grammar {
+        declarations: vec![
+            regex(
+                r"^(?:(?:public|private|protected|internal|static|final|abstract|sealed|export|typedef)\s+)*(?P<kind>class|interface|enum|struct|union|namespace|record|@interface)\b",
+            ),
+            regex(r"^#\s*(?P<kind>define)\b"),
+            // Functions and methods: a return type or modifiers, then a name.
+            regex(r"^(?:[\w<>\[\],.?*&:~]+[\s*&]+)+~?\w+\s*\("),
+        ],
+        members: vec![],
+        containers: &[
+            "class",
+            "interface",
+            "enum",
+            "struct",
+            "namespace",
+            "record",
+        ],
+    });
+
+    match language {
+        Language::Rust => Some(&RUST),
+        Language::Go => Some(&GO),
+        Language::JavaScript => Some(&JAVASCRIPT),
+        Language::CLike => Some(&C_LIKE),
+        Language::Python | Language::Toml | Language::Shell | Language::Yaml => None,
+    }
+}
+
+fn regex(pattern: &str) -> Regex {
+    Regex::new(pattern).expect("outline pattern is valid")
+}
+
+static PYTHON_DECLARATION: LazyLock<Regex> =
+    LazyLock::new(|| regex(r"^(?:async\s+)?(?P<kind>def|class)\b"));
+
+/// Lists the declarations in `text` with their line numbers, indented by
+/// nesting, instead of the full content. Members are listed for classes and
+/// similar containers, but nothing inside function bodies. Returns `None` for
+/// languages without an outline.
+pub fn outline(text: &str, language: Language) -> Option<String> {
+    let masked = mask(text, language);
+    let lines = text.lines().zip(masked.lines()).enumerate();
+
+    let declarations = if language == Language::Python {
+        indented_declarations(lines)
+    } else {
+        braced_declarations(lines, grammar(language)?)
+    };
+
+    let width = text.lines().count().to_string().len();
+    let mut result = String::new();
+    for (number, depth, declaration) in declarations {
+        result.push_str(&format!(
+            "{:>width$} | {}{declaration}\n",
+            number + 1,
+            "  ".repeat(depth)
+        ));
+    }
+    Some(result)
+}
+
+/// A line number, nesting depth and the declaration's text.
+type Declaration<'a> = (usize, usize, &'a str);
+
+fn braced_declarations<'a>(
+    lines: impl Iterator<Item = (usize, (&'a str, &'a str))>,
+    grammar: &Grammar,
+) -> Vec<Declaration<'a>> {
+    let mut declarations = vec![];
+    // Whether each open brace belongs to a container.
+    let mut braces: Vec<bool> = vec![];
+    // A container whose opening brace is on a later line.
+    let mut pending_container = false;
+
+    for (number, (line, masked)) in lines {
+        let trimmed = masked.trim_start();
+        let indent = masked.len() - trimmed.len();
+        let mut opens_container = false;
+
+        if braces.iter().all(|container| *container)
+            && let Some(kind) = match_declaration(grammar, trimmed, !braces.is_empty())
+        {
+            let end = trimmed.find('{').map_or(line.len(), |brace| indent + brace);
+            let declaration = line[indent..end].trim_end();
+            if !declaration.is_empty() {
+                declarations.push((number, braces.len(), declaration));
+            }
+            opens_container = kind.is_some_and(|kind| grammar.containers.contains(&kind));
+            pending_container = opens_container && !trimmed.contains('{');
+        }
+
+        for c in trimmed.chars() {
+            match c {
+                '{' => {
+                    braces.push(opens_container || pending_container);
+                    opens_container = false;
+                    pending_container = false;
+                }
+                '}' => {
+                    braces.pop();
+                }
+                ';' if pending_container => pending_container = false,
+                _ => {}
+            }
+        }
+    }
+    declarations
+}
+
+/// Matches a line against the grammar, returning the kind of declaration, if
+/// the pattern names one.
+fn match_declaration<'t>(
+    grammar: &Grammar,
+    line: &'t str,
+    member: bool,
+) -> Option<Option<&'t str>> {
+    let first_word = line
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .next();
+    if first_word.is_some_and(|word| CONTROL_KEYWORDS.contains(&word)) {
+        return None;
+    }
+    let members = if member { &grammar.members[..] } else { &[] };
+    grammar
+        .declarations
+        .iter()
+        .chain(members)
+        .find_map(|pattern| {
+            let captures = pattern.captures(line)?;
+            Some(captures.name("kind").map(|kind| kind.as_str()))
+        })
+}
+
+fn indented_declarations<'a>(
+    lines: impl Iterator<Item = (usize, (&'a str, &'a str))>,
+) -> Vec<Declaration<'a>> {
+    let mut declarations = vec![];
+    // The indentation of each enclosing declaration, and whether it's a class.
+    let mut blocks: Vec<(usize, bool)> = vec![];
+
+    for (number, (line, masked)) in lines {
+        let trimmed = masked.trim_start();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let indent = masked.len() - trimmed.len();
+        while blocks.last().is_some_and(|(block, _)| *block >= indent) {
+            blocks.pop();
+        }
+
+        if let Some(captures) = PYTHON_DECLARATION.captures(trimmed) {
+            if blocks.iter().all(|(_, class)| *class) {
+                let declaration = line[indent..].trim_end();
+                let declaration = declaration.strip_suffix(':').unwrap_or(declaration);
+                declarations.push((number, blocks.len(), declaration));
+            }
+            blocks.push((indent, &captures["kind"] == "class"));
+        }
+    }
+    declarations
+}
+
+/// Replaces strings and comments with spaces, keeping line breaks and byte
+/// offsets, so that their content isn't mistaken for code.
+fn mask(text: &str, language: Language) -> String {
+    let mut masked = String::with_capacity(text.len());
+    for segment in lexer::segments(text, language) {
+        if segment.kind == SegmentKind::Code {
+            masked.push_str(segment.text);
+            continue;
+        }
+        for c in segment.text.chars() {
+            if c == '\n' {
+                masked.push(c);
+            } else {
+                masked.extend(std::iter::repeat_n(' ', c.len_utf8()));
+            }
+        }
+    }
+    masked
+}
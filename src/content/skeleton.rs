@@ -0,0 +1,79 @@
+// MIT License
+//
+// Copyright (c) 2025 Dmytro Prokhorov
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+use proc_macro2::extra::DelimSpan;
+use syn::{ImplItemFn, ItemFn, TraitItemFn, visit::Visit};
+
+/// Reduces Rust source to its items: signatures, type definitions, trait
+/// declarations and doc comments are kept as written, while function bodies
+/// are replaced with `{ ... }`. Returns `None` if the source doesn't parse.
+pub fn rust(text: &str) -> Option<String> {
+    // syn would drop a shebang itself, but then spans would no longer line up
+    // with `text`.
+    let offset = match text.strip_prefix("#!") {
+        Some(rest) if !rest.trim_start().starts_with('[') => text.find('\n').unwrap_or(text.len()),
+        _ => 0,
+    };
+
+    let file: syn::File = syn::parse_str(&text[offset..]).ok()?;
+    let mut bodies = Bodies(vec![]);
+    bodies.visit_file(&file);
+    drop(file);
+    proc_macro2::extra::invalidate_current_thread_spans();
+
+    let mut skeleton = String::with_capacity(text.len());
+    let mut position = 0;
+    for body in bodies.0 {
+        let start = offset + body.start;
+        skeleton.push_str(&text[position..start]);
+        skeleton.push_str("{ ... }");
+        position = offset + body.end;
+    }
+    skeleton.push_str(&text[position..]);
+    Some(skeleton)
+}
+
+/// Byte ranges of the outermost function bodies, braces included, in order.
+struct Bodies(Vec<std::ops::Range<usize>>);
+
+impl Bodies {
+    fn push(&mut self, span: &DelimSpan) {
+        self.0.push(span.join().byte_range());
+    }
+}
+
+// Bodies aren't visited, so items nested in them disappear along with them.
+impl<'ast> Visit<'ast> for Bodies {
+    fn visit_item_fn(&mut self, item: &'ast ItemFn) {
+        self.push(&item.block.brace_token.span);
+    }
+
+    fn visit_impl_item_fn(&mut self, item: &'ast ImplItemFn) {
+        self.push(&item.block.brace_token.span);
+    }
+
+    fn visit_trait_item_fn(&mut self, item: &'ast TraitItemFn) {
+        if let Some(ref block) = item.default {
+            self.push(&block.brace_token.span);
+        }
+    }
+}
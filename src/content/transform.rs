@@ -0,0 +1,136 @@
+// MIT License
+//
+// Copyright (c) 2025 Dmytro Prokhorov
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+use crate::{
+    config::files::{Transform, TransformRule},
+    content::{
+        lexer::{self, Language},
+        limits,
+    },
+    secrets::{Finding, Scanner},
+};
+
+use std::path::Path;
+
+use color_eyre::eyre::{Result, WrapErr};
+use globset::{Glob, GlobMatcher};
+
+/// The pipelines from `[[files.transforms]]`, with their globs compiled.
+pub struct Transforms {
+    rules: Vec<(GlobMatcher, Vec<Transform>)>,
+}
+
+impl Transforms {
+    pub fn new(rules: &[TransformRule]) -> Result<Self> {
+        let rules = rules
+            .iter()
+            .map(|rule| {
+                let glob = Glob::new(&rule.glob).wrap_err_with(|| {
+                    format!("invalid glob '{}' in files.transforms", rule.glob)
+                })?;
+                Ok((glob.compile_matcher(), rule.steps.clone()))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { rules })
+    }
+
+    /// Whether any pipeline redacts, which needs a secret scanner even when
+    /// scanning is otherwise off.
+    pub fn redacts(&self) -> bool {
+        self.rules
+            .iter()
+            .flat_map(|(_, steps)| steps)
+            .any(|step| matches!(step, Transform::Redact))
+    }
+
+    /// Runs the pipeline of the first rule matching `path`.
+    pub fn apply(
+        &self,
+        path: &Path,
+        mut text: String,
+        scanner: Option<&Scanner>,
+        findings: &mut Vec<Finding>,
+    ) -> String {
+        let Some((_, steps)) = self.rules.iter().find(|(glob, _)| glob.is_match(path)) else {
+            return text;
+        };
+
+        for step in steps {
+            text = match step {
+                Transform::StripComments => match Language::from_path(path) {
+                    Some(language) => lexer::strip_comments(&text, language),
+                    None => text,
+                },
+                Transform::CollapseBlankLines => collapse_blank_lines(&text),
+                Transform::TrimTrailingWhitespace => trim_trailing_whitespace(&text),
+                Transform::NormalizeCrlf => text.replace("\r\n", "\n"),
+                Transform::LineNumbers => number_lines(&text),
+                Transform::Redact => match scanner {
+                    Some(scanner) => {
+                        let (redacted, found) = scanner.redact(path, &text);
+                        findings.extend(found);
+                        redacted
+                    }
+                    None => text,
+                },
+                Transform::Truncate { head, tail } => limits::truncate_lines(&text, *head, *tail),
+            };
+        }
+        text
+    }
+}
+
+/// Replaces runs of blank lines with a single empty line and drops those at
+/// the start and end.
+pub fn collapse_blank_lines(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut pending_blank = false;
+    for line in text.split_inclusive('\n') {
+        if line.trim().is_empty() {
+            pending_blank = !result.is_empty();
+            continue;
+        }
+        if pending_blank {
+            result.push('\n');
+            pending_blank = false;
+        }
+        result.push_str(line);
+    }
+    result
+}
+
+pub fn trim_trailing_whitespace(text: &str) -> String {
+    text.split_inclusive('\n')
+        .map(|line| {
+            let newline = if line.ends_with('\n') { "\n" } else { "" };
+            format!("{}{newline}", line.trim_end())
+        })
+        .collect()
+}
+
+fn number_lines(text: &str) -> String {
+    let width = text.lines().count().to_string().len();
+    text.split_inclusive('\n')
+        .enumerate()
+        .map(|(i, line)| format!("{:>width$} | {line}", i + 1))
+        .collect()
+}
@@ -0,0 +1,73 @@
+// MIT License
+//
+// Copyright (c) 2025 Dmytro Prokhorov
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+use crate::config::{files::ViewRule, selection::View};
+
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::{Result, WrapErr};
+use globset::{Glob, GlobMatcher};
+
+/// The view rules from `[[files.views]]`, with their globs compiled, and the
+/// focus paths that are always shown in full.
+pub struct Views {
+    rules: Vec<(GlobMatcher, View)>,
+    focus: Vec<PathBuf>,
+}
+
+impl Views {
+    pub fn new(rules: &[ViewRule]) -> Result<Self> {
+        let rules = rules
+            .iter()
+            .map(|rule| {
+                let glob = Glob::new(&rule.glob)
+                    .wrap_err_with(|| format!("invalid glob '{}' in files.views", rule.glob))?;
+                Ok((glob.compile_matcher(), rule.view))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            rules,
+            focus: vec![],
+        })
+    }
+
+    /// Repository-relative files, or directories, to show in full regardless
+    /// of their view.
+    pub fn set_focus(&mut self, focus: Vec<PathBuf>) {
+        self.focus = focus;
+    }
+
+    /// The view for `path`, given the one set on its selection entry.
+    pub fn resolve(&self, path: &Path, entry: Option<View>) -> View {
+        if self.focus.iter().any(|focus| path.starts_with(focus)) {
+            return View::Full;
+        }
+        entry
+            .or_else(|| {
+                self.rules
+                    .iter()
+                    .find(|(glob, _)| glob.is_match(path))
+                    .map(|(_, view)| *view)
+            })
+            .unwrap_or(View::Full)
+    }
+}
@@ -38,7 +38,5 @@ pub fn get_user_input_from_file_content(
 
     open_in_vim(&temp_path, cursor_line)?;
 
-    let result = fs::read_to_string(&temp_path).wrap_err("failed to read temporary file");
-
-    result
+    fs::read_to_string(&temp_path).wrap_err("failed to read temporary file")
 }
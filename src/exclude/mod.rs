@@ -0,0 +1,272 @@
+// MIT License
+//
+// Copyright (c) 2025 Dmytro Prokhorov
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+use crate::{config::files::ExcludeConfig, git};
+
+use std::{
+    collections::HashSet,
+    fmt,
+    fs::File,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::eyre::{Result, WrapErr};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use regex::RegexSet;
+
+/// Lockfiles and generated sources that are almost never worth sending to a model.
+const BUILTIN_GLOBS: &[&str] = &[
+    "**/Cargo.lock",
+    "**/package-lock.json",
+    "**/npm-shrinkwrap.json",
+    "**/yarn.lock",
+    "**/pnpm-lock.yaml",
+    "**/bun.lockb",
+    "**/composer.lock",
+    "**/Gemfile.lock",
+    "**/poetry.lock",
+    "**/Pipfile.lock",
+    "**/uv.lock",
+    "**/go.sum",
+    "**/flake.lock",
+    "**/mix.lock",
+    "**/pubspec.lock",
+    "**/Podfile.lock",
+    "**/*.min.js",
+    "**/*.min.css",
+    "**/*.js.map",
+    "**/*.css.map",
+    "**/*.pb.go",
+    "**/*.pb.cc",
+    "**/*.pb.h",
+    "**/*_pb2.py",
+    "**/*_pb2_grpc.py",
+    "**/*_pb2.pyi",
+];
+
+/// Files that commonly hold credentials.
+const SENSITIVE_GLOBS: &[&str] = &[
+    "**/.env",
+    "**/.env.*",
+    "**/*.pem",
+    "**/*.key",
+    "**/*.p12",
+    "**/*.pfx",
+    "**/*.jks",
+    "**/*.keystore",
+    "**/id_rsa",
+    "**/id_dsa",
+    "**/id_ecdsa",
+    "**/id_ed25519",
+    "**/.netrc",
+    "**/.npmrc",
+    "**/.pypirc",
+    "**/.git-credentials",
+    "**/credentials.json",
+    "**/service-account*.json",
+    "**/.aws/credentials",
+    "**/*.tfstate",
+];
+
+/// Comment lines that tools put near the top of the files they generate,
+/// after the ones linguist recognizes. Mentions of these phrases elsewhere,
+/// e.g. in docs or tests, don't count.
+const GENERATED_MARKERS: &[&str] = &[
+    r"^// Code generated .* DO NOT EDIT\.$",
+    r"^\s*(//|#|/?\*|--|;)\s*@generated\b",
+    r"^\s*(//|#|/?\*)\s*Generated by the protocol buffer compiler\.\s+DO NOT EDIT!",
+    r"^\s*(//|#|/?\*|<!--)\s*<auto-generated",
+    r"^\s*(//|#|/?\*|--)\s*This (file|code) (is|was|has been) (auto-?generated|automatically generated)\b",
+];
+
+/// How far into a file the content heuristics look.
+const MARKER_SNIFF_LEN: usize = 2048;
+pub const SNIFF_LEN: usize = 64 * 1024;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Reason {
+    Sensitive,
+    Builtin,
+    Pattern,
+    Marker,
+    LongLines,
+    Attribute,
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            Reason::Sensitive => "may contain credentials",
+            Reason::Builtin => "lockfile or generated",
+            Reason::Pattern => "excluded by pattern",
+            Reason::Marker => "generated-file marker",
+            Reason::LongLines => "minified",
+            Reason::Attribute => "linguist-generated",
+        };
+        write!(f, "{reason}")
+    }
+}
+
+/// Decides which files found in selected directories are left out automatically.
+pub struct Exclusions {
+    config: ExcludeConfig,
+    sensitive: GlobSet,
+    builtin: GlobSet,
+    patterns: GlobSet,
+    allow: GlobSet,
+    markers: RegexSet,
+    generated: HashSet<PathBuf>,
+}
+
+impl Exclusions {
+    pub fn new(config: &ExcludeConfig) -> Result<Self> {
+        let builtin = if config.builtin {
+            build_glob_set(BUILTIN_GLOBS.iter().copied())?
+        } else {
+            GlobSet::empty()
+        };
+        let sensitive = if config.sensitive {
+            build_glob_set(SENSITIVE_GLOBS.iter().copied())?
+        } else {
+            GlobSet::empty()
+        };
+        Ok(Self {
+            config: config.clone(),
+            sensitive,
+            builtin,
+            patterns: build_glob_set(config.globs.iter().map(String::as_str))?,
+            allow: build_glob_set(config.allow.iter().map(String::as_str))?,
+            markers: RegexSet::new(
+                GENERATED_MARKERS
+                    .iter()
+                    .map(|marker| format!("(?m){marker}")),
+            )
+            .wrap_err("failed to build generated-file markers")?,
+            generated: HashSet::new(),
+        })
+    }
+
+    /// Looks up the `linguist-generated` attribute for the candidate files in one go.
+    /// `paths` are relative to the project root, and the attributes are read at
+    /// `rev` if given. Outside a git repository there are no attributes to look up.
+    pub fn load_attributes(
+        &mut self,
+        project_root: &Path,
+        paths: &[PathBuf],
+        rev: Option<&str>,
+    ) -> Result<()> {
+        if self.config.heuristics && git::is_repo(project_root) {
+            self.generated = git::linguist_generated(project_root, paths, rev)?;
+        }
+        Ok(())
+    }
+
+    /// Returns whether a file is excluded for holding credentials. Unlike
+    /// [`Exclusions::check`], this applies to directly selected files too.
+    pub fn check_sensitive(&self, repo_path: &Path) -> Option<Reason> {
+        if !self.allow.is_match(repo_path) && self.sensitive.is_match(repo_path) {
+            Some(Reason::Sensitive)
+        } else {
+            None
+        }
+    }
+
+    /// Like [`Exclusions::check`], for a file whose content is already at
+    /// hand, e.g. because it was read from a git revision.
+    pub fn check_content(&self, repo_path: &Path, content: &[u8]) -> Option<Reason> {
+        self.check(repo_path, || Ok(content))
+            .expect("reading content at hand doesn't fail")
+    }
+
+    /// Returns why a file found by walking a selected directory should be
+    /// excluded, if it should. `repo_path` is relative to the repository root.
+    /// `read` is called for the content of the file, or at least its first
+    /// [`SNIFF_LEN`] bytes, only if the heuristics need it, so callers can
+    /// keep what it read.
+    pub fn check<'a>(
+        &self,
+        repo_path: &Path,
+        read: impl FnOnce() -> Result<&'a [u8]>,
+    ) -> Result<Option<Reason>> {
+        if self.allow.is_match(repo_path) {
+            return Ok(None);
+        }
+        if let Some(reason) = self.check_sensitive(repo_path) {
+            return Ok(Some(reason));
+        }
+        if self.patterns.is_match(repo_path) {
+            return Ok(Some(Reason::Pattern));
+        }
+        if self.builtin.is_match(repo_path) {
+            return Ok(Some(Reason::Builtin));
+        }
+        if !self.config.heuristics {
+            return Ok(None);
+        }
+        if self.generated.contains(repo_path) {
+            return Ok(Some(Reason::Attribute));
+        }
+
+        let content = read()?;
+        let head = &content[..content.len().min(SNIFF_LEN)];
+
+        // Binary files are handled when their content is emitted.
+        if head.contains(&0) {
+            return Ok(None);
+        }
+
+        let marker_region = String::from_utf8_lossy(&head[..head.len().min(MARKER_SNIFF_LEN)]);
+        if self.markers.is_match(&marker_region) {
+            return Ok(Some(Reason::Marker));
+        }
+
+        // A final line cut off by the sniff length still counts; such a file is
+        // long-lined either way.
+        if head
+            .split(|&byte| byte == b'\n')
+            .any(|line| line.len() > self.config.max_line_length)
+        {
+            return Ok(Some(Reason::LongLines));
+        }
+
+        Ok(None)
+    }
+}
+
+/// Reads the first [`SNIFF_LEN`] bytes of a file, which is all
+/// [`Exclusions::check`] needs.
+pub fn read_head(path: &Path) -> Result<Vec<u8>> {
+    let mut head = Vec::with_capacity(SNIFF_LEN);
+    File::open(path)
+        .and_then(|file| file.take(SNIFF_LEN as u64).read_to_end(&mut head))
+        .wrap_err_with(|| format!("failed to read file {}", path.display()))?;
+    Ok(head)
+}
+
+fn build_glob_set<'a>(globs: impl IntoIterator<Item = &'a str>) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for glob in globs {
+        builder.add(Glob::new(glob).wrap_err_with(|| format!("invalid glob '{glob}'"))?);
+    }
+    builder.build().wrap_err("failed to build glob set")
+}
@@ -22,7 +22,7 @@
 
 use crate::{
     config::{Config, Selection},
-    git,
+    root,
 };
 use color_eyre::eyre::{eyre, Result, WrapErr};
 use serde::{Deserialize, Serialize};
@@ -50,7 +50,7 @@ impl std::fmt::Display for FeatureName {
 }
 
 fn features_dir() -> Result<PathBuf> {
-    let dir = git::repo_root()?.join(".select").join("features");
+    let dir = root::storage_dir()?.join("features");
     if !dir.exists() {
         fs::create_dir_all(&dir).wrap_err("failed to create features directory")?;
     }
@@ -100,10 +100,10 @@ pub fn list() -> Result<Vec<FeatureName>> {
     for entry in fs::read_dir(dir)? {
         let entry = entry?;
         let path = entry.path();
-        if path.is_dir() {
-            if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                names.push(FeatureName::new(name.to_owned()));
-            }
+        if path.is_dir()
+            && let Some(name) = path.file_name().and_then(|n| n.to_str())
+        {
+            names.push(FeatureName::new(name.to_owned()));
         }
     }
     names.sort();
@@ -137,6 +137,7 @@ pub fn write_selection(name: &FeatureName, selection: &Selection) -> Result<()>
 
     let config_to_write = Config {
         selection: Some(selection.clone()),
+        ..Default::default()
     };
     let content =
         toml::to_string_pretty(&config_to_write).wrap_err("Failed to serialize selection")?;
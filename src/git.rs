@@ -20,13 +20,28 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
 // THE SOFTWARE.
 
-use std::{path::PathBuf, process::Command};
+use crate::config::selection::GitSource;
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::OnceLock,
+};
 
 use color_eyre::eyre::{Result, WrapErr, eyre};
 
+/// The mode git gives submodules in trees and the index.
+const SUBMODULE_MODE: &str = "160000";
+
+/// The id of the empty tree, which exists in every repository.
+const EMPTY_TREE: &str = "4b825dc642cb6eb9a060e54bf8d69288fbee4904";
+
 pub fn repo_root() -> Result<PathBuf> {
     let output = Command::new("git")
-        .args(&["rev-parse", "--show-toplevel"])
+        .args(["rev-parse", "--show-toplevel"])
         .output()
         .wrap_err("Failed to execute `git rev-parse --show-toplevel`. Is git installed?")?;
 
@@ -44,3 +59,895 @@ pub fn repo_root() -> Result<PathBuf> {
     let git_root = PathBuf::from(git_root_str.trim());
     Ok(git_root)
 }
+
+/// Returns the directory in the main worktree that corresponds to `dir`, if
+/// `dir` is in a linked worktree, as created by `git worktree add`.
+pub fn main_worktree_dir(dir: &Path) -> Result<Option<PathBuf>> {
+    let stdout = output(
+        dir,
+        &[
+            "rev-parse",
+            "--path-format=absolute",
+            "--git-dir",
+            "--git-common-dir",
+            "--show-prefix",
+        ],
+    )?;
+    let mut lines = stdout.lines();
+    let (Some(git_dir), Some(common_dir)) = (lines.next(), lines.next()) else {
+        return Err(eyre!("unexpected `git rev-parse` output: {stdout}"));
+    };
+    let prefix = lines.next().unwrap_or_default();
+
+    // The main worktree holds the common dir as its `.git`, unless the
+    // repository is bare.
+    let common_dir = Path::new(common_dir);
+    if git_dir == common_dir.as_os_str() || common_dir.file_name() != Some(".git".as_ref()) {
+        return Ok(None);
+    }
+    Ok(common_dir.parent().map(|main| main.join(prefix)))
+}
+
+/// Reads the boolean git configuration `key` in `dir`, or `default` if unset.
+pub fn config_bool(dir: &Path, key: &str, default: bool) -> Result<bool> {
+    let default = default.to_string();
+    let value = output(
+        dir,
+        &["config", "--type=bool", "--default", &default, "--get", key],
+    )?;
+    Ok(value.trim() == "true")
+}
+
+/// Whether `dir` is inside a git working tree.
+pub fn is_repo(dir: &Path) -> bool {
+    output(dir, &["rev-parse", "--is-inside-work-tree"]).is_ok_and(|stdout| stdout.trim() == "true")
+}
+
+/// Returns the subset of `paths` (relative to `root`) that `.gitattributes`
+/// marks as `linguist-generated`, reading the attributes from `rev` if given
+/// and from the working tree otherwise. At `rev`, like in [`read_blobs`],
+/// paths in a checked-out submodule get the attributes of the commit it is at.
+pub fn linguist_generated(
+    root: &Path,
+    paths: &[PathBuf],
+    rev: Option<&str>,
+) -> Result<HashSet<PathBuf>> {
+    let Some(rev) = rev else {
+        return check_generated(root, paths, None);
+    };
+
+    let submodules = submodules_at(root, rev)?;
+    let (submodule_paths, own_paths): (Vec<_>, Vec<_>) = paths.iter().cloned().partition(|path| {
+        submodules
+            .iter()
+            .any(|(submodule, _)| path.starts_with(submodule))
+    });
+    let mut generated = check_generated(root, &own_paths, Some(rev))?;
+    for (submodule, commit) in &submodules {
+        let submodule_root = root.join(submodule);
+        let rests: Vec<_> = submodule_paths
+            .iter()
+            .filter_map(|path| path.strip_prefix(submodule).ok())
+            .map(Path::to_path_buf)
+            .collect();
+        if rests.is_empty() || !is_populated_at(&submodule_root, Some(commit)) {
+            continue;
+        }
+        let submodule_generated = linguist_generated(&submodule_root, &rests, Some(commit))?;
+        generated.extend(
+            submodule_generated
+                .into_iter()
+                .map(|path| submodule.join(path)),
+        );
+    }
+    Ok(generated)
+}
+
+/// Runs `git check-attr` for [`linguist_generated`] on `paths`, which aren't
+/// in submodules if `rev` is given.
+fn check_generated(root: &Path, paths: &[PathBuf], rev: Option<&str>) -> Result<HashSet<PathBuf>> {
+    if paths.is_empty() {
+        return Ok(HashSet::new());
+    }
+
+    let mut command = Command::new("git");
+    command.current_dir(root);
+    // Keeps the temporary index alive until `check-attr` is done.
+    let mut _index_dir = None;
+    match rev {
+        Some(rev) if supports_attr_source(root) => {
+            command.args(["check-attr", "--source", rev]);
+        }
+        Some(rev) => {
+            // Before git 2.40, `check-attr` can only read attributes from the
+            // working tree or the index, so read `rev` into a scratch index.
+            let dir = tempfile::tempdir().wrap_err("failed to create a temporary index")?;
+            let index = dir.path().join("index");
+            let status = Command::new("git")
+                .args(["read-tree", "--end-of-options", rev])
+                .env("GIT_INDEX_FILE", &index)
+                .current_dir(root)
+                .stdout(Stdio::null())
+                .status()
+                .wrap_err("Failed to execute `git read-tree`. Is git installed?")?;
+            if !status.success() {
+                return Err(eyre!("`git read-tree {rev}` failed"));
+            }
+            command
+                .env("GIT_INDEX_FILE", &index)
+                .args(["check-attr", "--cached"]);
+            _index_dir = Some(dir);
+        }
+        None => {
+            command.arg("check-attr");
+        }
+    }
+
+    let mut child = command
+        .args(["-z", "--stdin", "linguist-generated"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .wrap_err("Failed to execute `git check-attr`. Is git installed?")?;
+
+    let mut input = Vec::new();
+    for path in paths {
+        input.extend_from_slice(path.to_string_lossy().as_bytes());
+        input.push(0);
+    }
+    let mut stdin = child.stdin.take().expect("stdin was piped");
+    let writer = std::thread::spawn(move || stdin.write_all(&input));
+
+    let output = child
+        .wait_with_output()
+        .wrap_err("failed to wait for `git check-attr`")?;
+    writer
+        .join()
+        .expect("writer thread panicked")
+        .wrap_err("failed to write paths to `git check-attr`")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(eyre!("`git check-attr` failed: {}", stderr.trim()));
+    }
+
+    // With `-z`, the output is a sequence of `<path> NUL <attribute> NUL <value> NUL`.
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let fields: Vec<&str> = stdout.split('\0').collect();
+    let generated = fields
+        .chunks_exact(3)
+        .filter(|chunk| matches!(chunk[2], "set" | "true"))
+        .map(|chunk| PathBuf::from(chunk[0]))
+        .collect();
+
+    Ok(generated)
+}
+
+/// Whether `git check-attr` takes `--source`, which came with git 2.40.
+fn supports_attr_source(root: &Path) -> bool {
+    static SUPPORTED: OnceLock<bool> = OnceLock::new();
+    *SUPPORTED.get_or_init(|| {
+        let Ok(version) = output(root, &["version"]) else {
+            return false;
+        };
+        // E.g. `git version 2.39.5` or `git version 2.45.1.windows.1`.
+        let mut numbers = version
+            .trim()
+            .trim_start_matches("git version ")
+            .split('.')
+            .map(|part| part.parse::<u32>().unwrap_or(0));
+        let major = numbers.next().unwrap_or(0);
+        let minor = numbers.next().unwrap_or(0);
+        (major, minor) >= (2, 40)
+    })
+}
+
+/// Returns the files, relative to `root`, that `source` stands for. Deleted
+/// files and files outside `root` are left out, and changes to checked-out
+/// submodules are listed file by file.
+///
+/// With `rev`, `source` is evaluated as of that commit: `@since` compares
+/// against it instead of the working tree, and the sources that describe
+/// uncommitted work (`@changed`, `@staged`, `@untracked`) stand for nothing.
+pub fn source_files(root: &Path, source: &GitSource, rev: Option<&str>) -> Result<Vec<PathBuf>> {
+    match (source, rev) {
+        (GitSource::Changed | GitSource::Staged | GitSource::Untracked, Some(_)) => Ok(Vec::new()),
+        (GitSource::Changed, None) => changed_files(root, &["diff"], &["HEAD"], true),
+        (GitSource::Staged, None) => changed_files(root, &["diff", "--cached"], &[], false),
+        (GitSource::Untracked, None) => untracked_files(root),
+        (GitSource::Since(base), Some(rev)) => {
+            changed_files(root, &["diff", "--merge-base"], &[base, rev], false)
+        }
+        (GitSource::Since(base), None) => {
+            changed_files(root, &["diff", "--merge-base"], &[base], true)
+        }
+        (GitSource::Commit(rev), _) => changed_files(
+            root,
+            &["diff-tree", "--no-commit-id", "--root", "-r"],
+            &[rev],
+            false,
+        ),
+    }
+}
+
+/// Runs `git diff` or `git diff-tree` with the options in `args` on `revs`
+/// and returns the files it reports, descending into the submodules it
+/// reports. `worktree` says whether the comparison is against the working
+/// tree, which then also applies to the submodules.
+fn changed_files(
+    root: &Path,
+    args: &[&str],
+    revs: &[&str],
+    worktree: bool,
+) -> Result<Vec<PathBuf>> {
+    let (command, args) = args.split_first().expect("a git command");
+    let mut full_args = vec![
+        *command,
+        "--raw",
+        "-z",
+        "--no-abbrev",
+        "--no-renames",
+        "--relative",
+        "--diff-filter=d",
+    ];
+    full_args.extend(args);
+    // Revisions from a shared `select.toml` mustn't pass for options.
+    full_args.push("--end-of-options");
+    full_args.extend(revs);
+    full_args.push("--");
+
+    // Each entry is `:<mode> SP <mode> SP <object> SP <object> SP <status>`
+    // followed by the path.
+    let stdout = output(root, &full_args)?;
+    let mut fields = stdout.split('\0').filter(|field| !field.is_empty());
+    let mut files = vec![];
+    while let (Some(info), Some(path)) = (fields.next(), fields.next()) {
+        let info: Vec<_> = info.trim_start_matches(':').split(' ').collect();
+        let [old_mode, new_mode, old, new, ..] = info[..] else {
+            return Err(eyre!(
+                "unexpected `git {command}` output: {}",
+                info.join(" ")
+            ));
+        };
+
+        let path = PathBuf::from(path);
+        if new_mode != SUBMODULE_MODE {
+            files.push(path);
+            continue;
+        }
+        if !is_populated(&root.join(&path)) {
+            continue;
+        }
+
+        let old = if old_mode == SUBMODULE_MODE {
+            old
+        } else {
+            EMPTY_TREE
+        };
+        let mut submodule_revs = vec![old];
+        if !worktree {
+            submodule_revs.push(new);
+        }
+        let submodule_files =
+            changed_files(&root.join(&path), &["diff"], &submodule_revs, worktree)?;
+        files.extend(submodule_files.into_iter().map(|file| path.join(file)));
+    }
+    Ok(files)
+}
+
+/// Returns the untracked files that aren't ignored, including those in
+/// checked-out submodules.
+fn untracked_files(root: &Path) -> Result<Vec<PathBuf>> {
+    let stdout = output(root, &["ls-files", "-z", "--others", "--exclude-standard"])?;
+    let mut files: Vec<_> = stdout
+        .split('\0')
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+        .collect();
+
+    for submodule in submodules(root)? {
+        let submodule_files = untracked_files(&root.join(&submodule))?;
+        files.extend(submodule_files.into_iter().map(|file| submodule.join(file)));
+    }
+    Ok(files)
+}
+
+/// Returns the checked-out submodules below `root`, relative to it. Nested
+/// submodules aren't included.
+pub fn submodules(root: &Path) -> Result<Vec<PathBuf>> {
+    // Each entry is `<mode> SP <object> SP <stage> TAB <path>`.
+    let stdout = output(root, &["ls-files", "-z", "--stage"])?;
+    Ok(stdout
+        .split('\0')
+        .filter_map(|entry| entry.split_once('\t'))
+        .filter(|(info, _)| info.starts_with(SUBMODULE_MODE))
+        .map(|(_, path)| PathBuf::from(path))
+        .filter(|path| is_populated(&root.join(path)))
+        .collect())
+}
+
+/// Returns the submodules in the tree of `rev` below `root`, relative to it,
+/// with the commit each one is at. Nested submodules aren't included.
+fn submodules_at(root: &Path, rev: &str) -> Result<Vec<(PathBuf, String)>> {
+    // Each entry is `<mode> SP <type> SP <object> TAB <path>`, and `-d` leaves
+    // out files.
+    let stdout = output(root, &["ls-tree", "-z", "-r", "-d", rev])?;
+    Ok(stdout
+        .split('\0')
+        .filter_map(|entry| entry.split_once('\t'))
+        .filter_map(
+            |(info, path)| match info.split(' ').collect::<Vec<_>>()[..] {
+                [_, "commit", commit] => Some((PathBuf::from(path), commit.to_owned())),
+                _ => None,
+            },
+        )
+        .collect())
+}
+
+/// Whether the submodule at `dir` is checked out, and has `commit` if given.
+fn is_populated_at(dir: &Path, commit: Option<&str>) -> bool {
+    if !dir.join(".git").exists() {
+        return false;
+    }
+    commit.is_none_or(|commit| {
+        output(dir, &["cat-file", "-e", &format!("{commit}^{{commit}}")]).is_ok()
+    })
+}
+
+fn is_populated(dir: &Path) -> bool {
+    is_populated_at(dir, None)
+}
+
+/// Returns the unified diff of `pathspecs` between `rev` and the working tree,
+/// or the index if `staged` is set. Without pathspecs the diff is empty.
+pub fn diff(root: &Path, rev: &str, staged: bool, pathspecs: &[String]) -> Result<String> {
+    diff_in(root, Path::new(""), rev, staged, pathspecs)
+}
+
+/// Like [`diff`], with the paths in the diff prefixed by `prefix`. Pathspecs
+/// that point into a checked-out submodule are diffed there, against the
+/// commit the submodule is at in `rev`.
+fn diff_in(
+    root: &Path,
+    prefix: &Path,
+    rev: &str,
+    staged: bool,
+    pathspecs: &[String],
+) -> Result<String> {
+    if pathspecs.is_empty() {
+        return Ok(String::new());
+    }
+
+    let submodules = submodules(root)?;
+    let (own_pathspecs, submodule_pathspecs) = split_pathspecs(&submodules, pathspecs);
+
+    let mut diff = String::new();
+    if !own_pathspecs.is_empty() {
+        let src_prefix = format!("--src-prefix=a/{}", dir_prefix(prefix));
+        let dst_prefix = format!("--dst-prefix=b/{}", dir_prefix(prefix));
+        let mut args = vec![
+            "diff",
+            "--no-color",
+            "--no-ext-diff",
+            "--relative",
+            "--submodule=diff",
+            &src_prefix,
+            &dst_prefix,
+        ];
+        if staged {
+            args.push("--cached");
+        }
+        args.extend(["--end-of-options", rev, "--"]);
+        args.extend(own_pathspecs.iter().map(String::as_str));
+        diff.push_str(&output_lossy(root, &args)?);
+    }
+
+    for (submodule, pathspecs) in submodule_pathspecs {
+        let spec = format!("{rev}:./{}", submodule.display());
+        // Submodules that didn't exist at `rev` have nothing to compare with.
+        let Ok(commit) = verify(root, &spec) else {
+            continue;
+        };
+        diff.push_str(&diff_in(
+            &root.join(submodule),
+            &prefix.join(submodule),
+            &commit,
+            staged,
+            &pathspecs,
+        )?);
+    }
+    Ok(diff)
+}
+
+/// Returns the files, relative to `root`, that [`diff`] with the same
+/// arguments would show, including deleted ones.
+pub fn diff_files(
+    root: &Path,
+    rev: &str,
+    staged: bool,
+    pathspecs: &[String],
+) -> Result<Vec<PathBuf>> {
+    if pathspecs.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let submodules = submodules(root)?;
+    let (own_pathspecs, mut submodule_pathspecs) = split_pathspecs(&submodules, pathspecs);
+
+    let mut files = vec![];
+    if !own_pathspecs.is_empty() {
+        let mut args = vec!["diff", "--name-only", "-z", "--no-renames", "--relative"];
+        if staged {
+            args.push("--cached");
+        }
+        args.extend(["--end-of-options", rev, "--"]);
+        args.extend(own_pathspecs.iter().map(String::as_str));
+        let stdout = output(root, &args)?;
+        for path in stdout.split('\0').filter(|path| !path.is_empty()) {
+            // The diff of a checked-out submodule covers the files in it.
+            match submodules
+                .iter()
+                .find(|submodule| *submodule == Path::new(path))
+            {
+                Some(submodule) => submodule_pathspecs.push((submodule, vec![".".to_owned()])),
+                None => files.push(PathBuf::from(path)),
+            }
+        }
+    }
+
+    for (submodule, pathspecs) in submodule_pathspecs {
+        let spec = format!("{rev}:./{}", submodule.display());
+        let Ok(commit) = verify(root, &spec) else {
+            continue;
+        };
+        let submodule_files = diff_files(&root.join(submodule), &commit, staged, &pathspecs)?;
+        files.extend(submodule_files.into_iter().map(|file| submodule.join(file)));
+    }
+    Ok(files)
+}
+
+/// Splits `pathspecs` into those for `root` itself and those that point into
+/// one of its `submodules`, made relative to the submodule. Either side is
+/// empty rather than holding only exclusions, which git would take as
+/// excluding from everything.
+///
+/// Exclusions are assumed to be within the other pathspecs. A submodule with
+/// only exclusions is covered by a pathspec of `root`, so it's diffed on its
+/// own instead, where the exclusions apply.
+fn split_pathspecs<'a>(
+    submodules: &'a [PathBuf],
+    pathspecs: &'a [String],
+) -> (Vec<String>, Vec<(&'a Path, Vec<String>)>) {
+    let mut own_pathspecs = vec![];
+    let mut submodule_pathspecs: Vec<(&Path, Vec<String>)> = vec![];
+    for pathspec in pathspecs {
+        // Pathspecs like `:(glob)src/*` start with their magic.
+        let (magic, path) = match pathspec.find(')') {
+            Some(end) if pathspec.starts_with(":(") => pathspec.split_at(end + 1),
+            _ => ("", pathspec.as_str()),
+        };
+        let inner = submodules.iter().find_map(|submodule| {
+            let rest = Path::new(path).strip_prefix(submodule).ok()?;
+            (!rest.as_os_str().is_empty()).then_some((submodule.as_path(), rest))
+        });
+        match inner {
+            Some((submodule, rest)) => {
+                let pathspec = format!("{magic}{}", rest.display());
+                match submodule_pathspecs
+                    .iter_mut()
+                    .find(|(path, _)| *path == submodule)
+                {
+                    Some((_, pathspecs)) => pathspecs.push(pathspec),
+                    None => submodule_pathspecs.push((submodule, vec![pathspec])),
+                }
+            }
+            None => own_pathspecs.push(pathspec.clone()),
+        }
+    }
+
+    for (submodule, pathspecs) in &mut submodule_pathspecs {
+        if pathspecs.iter().all(|pathspec| is_exclusion(pathspec)) {
+            pathspecs.push(".".to_owned());
+            own_pathspecs.push(format!(":(exclude,literal){}", submodule.display()));
+        }
+    }
+    if own_pathspecs.iter().all(|pathspec| is_exclusion(pathspec)) {
+        own_pathspecs.clear();
+    }
+    (own_pathspecs, submodule_pathspecs)
+}
+
+fn is_exclusion(pathspec: &str) -> bool {
+    match pathspec
+        .strip_prefix(":(")
+        .and_then(|rest| rest.split_once(')'))
+    {
+        Some((magic, _)) => magic.split(',').any(|word| word == "exclude"),
+        None => pathspec.starts_with(":!") || pathspec.starts_with(":^"),
+    }
+}
+
+/// Formats `dir` to prefix paths with, with a trailing slash unless empty.
+fn dir_prefix(dir: &Path) -> String {
+    match dir.display().to_string() {
+        dir if dir.is_empty() => dir,
+        dir => format!("{dir}/"),
+    }
+}
+
+/// Returns up to `count` commits reachable from `rev` that touch `pathspecs`,
+/// newest first, each with its message and, if `stat` is set, a diffstat.
+/// Without pathspecs there are no commits.
+pub fn log(
+    root: &Path,
+    rev: &str,
+    count: usize,
+    stat: bool,
+    pathspecs: &[String],
+) -> Result<Vec<String>> {
+    if pathspecs.is_empty() || count == 0 {
+        return Ok(vec![]);
+    }
+
+    let count = format!("--max-count={count}");
+    // Commits start with a record separator, as messages can hold anything else.
+    let mut args = vec![
+        "log",
+        "--no-color",
+        "--date=short",
+        "--format=%x1ecommit %h%nAuthor: %an%nDate:   %ad%n%n%B",
+        &count,
+    ];
+    if stat {
+        args.push("--stat");
+    }
+    args.extend(["--end-of-options", rev, "--"]);
+    args.extend(pathspecs.iter().map(String::as_str));
+
+    let stdout = output_lossy(root, &args)?;
+    Ok(stdout
+        .split('\x1e')
+        .map(str::trim)
+        .filter(|commit| !commit.is_empty())
+        // The message ends with a line break and the diffstat starts with one.
+        .map(|commit| commit.replace("\n\n\n", "\n\n"))
+        .collect())
+}
+
+/// How a file in the working tree differs from `HEAD`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FileStatus {
+    Modified,
+    Added,
+    Renamed,
+    Untracked,
+}
+
+impl fmt::Display for FileStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = match self {
+            FileStatus::Modified => "modified",
+            FileStatus::Added => "added",
+            FileStatus::Renamed => "renamed",
+            FileStatus::Untracked => "untracked",
+        };
+        write!(f, "{status}")
+    }
+}
+
+/// Returns the files, relative to `root`, with uncommitted changes, staged or
+/// not, including those in checked-out submodules. Deleted files and files
+/// outside `root` are left out.
+pub fn statuses(root: &Path) -> Result<HashMap<PathBuf, FileStatus>> {
+    // Unlike most commands, status prints paths relative to the top level.
+    let prefix = output(root, &["rev-parse", "--show-prefix"])?;
+    let prefix = Path::new(prefix.trim_end());
+
+    let stdout = output(
+        root,
+        &[
+            "status",
+            "--porcelain=v1",
+            "-z",
+            "--untracked-files=all",
+            "--ignore-submodules=all",
+        ],
+    )?;
+
+    // Each entry is `XY SP <path>`, followed by the original path for renames.
+    let mut statuses = HashMap::new();
+    let mut entries = stdout.split('\0');
+    while let Some(entry) = entries.next() {
+        let Some((code, path)) = entry.split_at_checked(3) else {
+            continue;
+        };
+        let status = match code.trim_end() {
+            "??" => FileStatus::Untracked,
+            code if code.starts_with('R') || code.starts_with('C') => {
+                entries.next();
+                FileStatus::Renamed
+            }
+            code if code.contains('D') => continue,
+            code if code.contains('A') => FileStatus::Added,
+            _ => FileStatus::Modified,
+        };
+        if let Ok(path) = Path::new(path).strip_prefix(prefix) {
+            statuses.insert(path.to_path_buf(), status);
+        }
+    }
+
+    for submodule in submodules(root)? {
+        for (path, status) in self::statuses(&root.join(&submodule))? {
+            statuses.insert(submodule.join(path), status);
+        }
+    }
+    Ok(statuses)
+}
+
+/// Resolves `rev` to the id of the commit it names.
+pub fn resolve_commit(root: &Path, rev: &str) -> Result<String> {
+    let spec = format!("{rev}^{{commit}}");
+    verify(root, &spec).wrap_err_with(|| format!("'{rev}' is not a commit"))
+}
+
+/// Resolves `spec` to an object id with `git rev-parse --verify`.
+fn verify(root: &Path, spec: &str) -> Result<String> {
+    let stdout = output(
+        root,
+        &["rev-parse", "--verify", "--quiet", "--end-of-options", spec],
+    )?;
+    Ok(stdout.trim().to_owned())
+}
+
+/// Lists the files in the tree of `rev` under `path`, both relative to `root`.
+/// Only direct children of a directory are listed unless `recursive` is set,
+/// and an empty `path` stands for all of `root`. Checked-out submodules are
+/// listed at the commit they are at in `rev`.
+pub fn ls_tree(root: &Path, rev: &str, path: &Path, recursive: bool) -> Result<Vec<PathBuf>> {
+    let submodules = submodules_at(root, rev)?;
+    if let Some((submodule, commit)) = submodules
+        .iter()
+        .find(|(submodule, _)| path.starts_with(submodule))
+    {
+        if !is_populated_at(&root.join(submodule), Some(commit)) {
+            return Ok(vec![]);
+        }
+        let rest = path
+            .strip_prefix(submodule)
+            .expect("path is in the submodule");
+        let files = ls_tree(&root.join(submodule), commit, rest, recursive)?;
+        return Ok(files.into_iter().map(|file| submodule.join(file)).collect());
+    }
+
+    let mut args = vec!["ls-tree", "-z"];
+    if recursive {
+        args.push("-r");
+    }
+    args.extend([rev, "--"]);
+
+    // A trailing slash lists a directory's children rather than the directory.
+    let mut pathspec = path.display().to_string();
+    if !pathspec.is_empty() {
+        if !recursive && !pathspec.ends_with('/') {
+            pathspec.push('/');
+        }
+        args.push(&pathspec);
+    }
+
+    // Each entry is `<mode> SP <type> SP <object> TAB <path>`.
+    let stdout = output(root, &args)?;
+    let mut files = vec![];
+    for (info, file) in stdout
+        .split('\0')
+        .filter_map(|entry| entry.split_once('\t'))
+    {
+        match info.split(' ').nth(1) {
+            Some("blob") => files.push(PathBuf::from(file)),
+            // Like directories, submodules are only walked recursively.
+            Some("commit") if recursive => files.extend(ls_tree(root, rev, Path::new(file), true)?),
+            _ => {}
+        }
+    }
+    Ok(files)
+}
+
+/// Reads the content of `paths`, relative to `root`, at `rev` in one go. Paths
+/// that don't exist at `rev` are `None`. Paths in a checked-out submodule are
+/// read at the commit it is at in `rev`.
+pub fn read_blobs(root: &Path, rev: &str, paths: &[PathBuf]) -> Result<Vec<Option<Vec<u8>>>> {
+    if paths.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let mut blobs = vec![None; paths.len()];
+    let mut own_paths = vec![];
+    let mut submodule_paths: Vec<(&Path, &str, Vec<usize>)> = vec![];
+    let submodules = submodules_at(root, rev)?;
+    for (index, path) in paths.iter().enumerate() {
+        match submodules
+            .iter()
+            .find(|(submodule, _)| path.starts_with(submodule))
+        {
+            Some((submodule, commit)) => {
+                match submodule_paths
+                    .iter_mut()
+                    .find(|(path, _, _)| *path == submodule)
+                {
+                    Some((_, _, indices)) => indices.push(index),
+                    None => submodule_paths.push((submodule, commit, vec![index])),
+                }
+            }
+            None => own_paths.push(index),
+        }
+    }
+
+    let own_blobs = cat_blobs(root, rev, own_paths.iter().map(|&index| &paths[index]))?;
+    for (index, blob) in own_paths.into_iter().zip(own_blobs) {
+        blobs[index] = blob;
+    }
+
+    for (submodule, commit, indices) in submodule_paths {
+        let submodule_root = root.join(submodule);
+        if !is_populated_at(&submodule_root, Some(commit)) {
+            continue;
+        }
+        let rests: Vec<_> = indices
+            .iter()
+            .map(|&index| {
+                paths[index]
+                    .strip_prefix(submodule)
+                    .unwrap_or(&paths[index])
+                    .to_path_buf()
+            })
+            .collect();
+        for (index, blob) in indices
+            .into_iter()
+            .zip(read_blobs(&submodule_root, commit, &rests)?)
+        {
+            blobs[index] = blob;
+        }
+    }
+
+    Ok(blobs)
+}
+
+/// Reads the content of `paths`, which aren't in submodules, at `rev` with a
+/// single `git cat-file`.
+fn cat_blobs<'a>(
+    root: &Path,
+    rev: &str,
+    paths: impl ExactSizeIterator<Item = &'a PathBuf>,
+) -> Result<Vec<Option<Vec<u8>>>> {
+    let count = paths.len();
+    if count == 0 {
+        return Ok(vec![]);
+    }
+
+    let mut input = Vec::new();
+    for path in paths {
+        writeln!(input, "{rev}:./{}", path.display()).expect("writing to a Vec doesn't fail");
+    }
+
+    let mut child = Command::new("git")
+        .args(["cat-file", "--batch"])
+        .current_dir(root)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .wrap_err("Failed to execute `git cat-file`. Is git installed?")?;
+
+    let mut stdin = child.stdin.take().expect("stdin was piped");
+    let writer = std::thread::spawn(move || stdin.write_all(&input));
+
+    let output = child
+        .wait_with_output()
+        .wrap_err("failed to wait for `git cat-file`")?;
+    writer
+        .join()
+        .expect("writer thread panicked")
+        .wrap_err("failed to write paths to `git cat-file`")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(eyre!("`git cat-file` failed: {}", stderr.trim()));
+    }
+
+    // Each object is `<id> <type> <size> LF <content> LF`, or `<name> missing LF`.
+    let mut blobs = Vec::with_capacity(count);
+    let mut rest = &output.stdout[..];
+    for _ in 0..count {
+        let header_end = rest
+            .iter()
+            .position(|&byte| byte == b'\n')
+            .ok_or_else(|| eyre!("unexpected end of `git cat-file` output"))?;
+        let header = String::from_utf8_lossy(&rest[..header_end]).into_owned();
+        rest = &rest[header_end + 1..];
+
+        let mut fields = header.rsplitn(3, ' ');
+        let (size, kind) = (fields.next(), fields.next());
+        match (kind, size.and_then(|size| size.parse::<usize>().ok())) {
+            (Some("blob"), Some(size)) if rest.len() > size => {
+                blobs.push(Some(rest[..size].to_vec()));
+                rest = &rest[size + 1..];
+            }
+            (Some(_), Some(size)) if rest.len() > size => {
+                // A tree or submodule rather than a file.
+                blobs.push(None);
+                rest = &rest[size + 1..];
+            }
+            _ => blobs.push(None),
+        }
+    }
+
+    Ok(blobs)
+}
+
+/// Runs git in `root` and returns its standard output.
+fn output(root: &Path, args: &[&str]) -> Result<String> {
+    let stdout = output_bytes(root, args)?;
+    String::from_utf8(stdout)
+        .wrap_err_with(|| format!("`git {}` output was not valid UTF-8", args.join(" ")))
+}
+
+/// Like [`output`], for output that quotes file contents or commit messages,
+/// which needn't be UTF-8. Invalid bytes are replaced rather than failing the
+/// whole render.
+fn output_lossy(root: &Path, args: &[&str]) -> Result<String> {
+    let stdout = output_bytes(root, args)?;
+    Ok(String::from_utf8_lossy(&stdout).into_owned())
+}
+
+/// Runs git in `root` and returns its raw standard output.
+fn output_bytes(root: &Path, args: &[&str]) -> Result<Vec<u8>> {
+    let command = format!("git {}", args.join(" "));
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(root)
+        .output()
+        .wrap_err_with(|| format!("Failed to execute `{command}`. Is git installed?"))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(eyre!("`{command}` failed: {}", stderr.trim()));
+    }
+
+    Ok(output.stdout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fs;
+
+    /// Runs git in `root` with a fixed identity, panicking on failure.
+    fn git(root: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(root)
+            .stdout(Stdio::null())
+            .status()
+            .expect("git runs");
+        assert!(status.success(), "git {} failed", args.join(" "));
+    }
+
+    #[test]
+    fn diff_of_non_utf8_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        git(root, &["init", "-q"]);
+        // "café" in Latin-1.
+        fs::write(root.join("latin1.txt"), b"caf\xe9\n").unwrap();
+        git(root, &["add", "latin1.txt"]);
+        git(root, &["commit", "-q", "-m", "init"]);
+        fs::write(root.join("latin1.txt"), b"caf\xe9 cr\xe8me\n").unwrap();
+
+        let diff = diff(root, "HEAD", false, &["latin1.txt".to_owned()]).unwrap();
+
+        assert!(diff.contains("-caf\u{fffd}\n"), "{diff}");
+        assert!(diff.contains("+caf\u{fffd} cr\u{fffd}me\n"), "{diff}");
+    }
+}
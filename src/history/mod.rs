@@ -0,0 +1,296 @@
+// MIT License
+//
+// Copyright (c) 2025 Dmytro Prokhorov
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+use crate::{feature::FeatureName, root, token::TokenCount};
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs, io,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use color_eyre::eyre::{Report, Result, WrapErr, eyre};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Identifies a recorded render. IDs are assigned sequentially, starting at 1.
+pub type RenderId = u64;
+
+/// A file that was part of a rendered prompt.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedFile {
+    /// Path relative to the repository root.
+    pub path: PathBuf,
+    /// SHA-256 of the file content, hex-encoded.
+    pub hash: String,
+    /// SHA-256 of the text the file was rendered as, under which that text is
+    /// kept in the object store. Unset for files that weren't rendered as text.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub object: Option<String>,
+}
+
+impl RecordedFile {
+    pub fn new(project_root: &Path, abs_path: &Path, content: &[u8]) -> Self {
+        Self {
+            path: abs_path
+                .strip_prefix(project_root)
+                .unwrap_or(abs_path)
+                .to_path_buf(),
+            hash: hash_content(content),
+            object: None,
+        }
+    }
+}
+
+/// Everything needed to trace a rendered prompt back to what produced it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Manifest {
+    pub id: RenderId,
+    /// Seconds since the unix epoch.
+    pub timestamp: u64,
+    pub template: String,
+    pub feature: Option<String>,
+    pub model: Option<String>,
+    /// The render this one was a delta against, for `--since-last` renders.
+    pub base: Option<RenderId>,
+    /// The commit file contents were read from, for `--rev` renders.
+    pub rev: Option<String>,
+    pub token_count: TokenCount,
+    pub task: String,
+    pub files: Vec<RecordedFile>,
+}
+
+/// What a render is recorded with. [`record`] assigns the rest of the
+/// [`Manifest`].
+#[derive(Debug, Clone)]
+pub struct NewRender {
+    pub template: String,
+    pub feature: Option<String>,
+    pub model: Option<String>,
+    pub base: Option<RenderId>,
+    pub rev: Option<String>,
+    pub token_count: TokenCount,
+    pub task: String,
+    pub files: Vec<RecordedFile>,
+}
+
+impl Manifest {
+    pub fn time(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.timestamp)
+    }
+
+    pub fn feature_name(&self) -> Option<FeatureName> {
+        self.feature.clone().map(FeatureName::new)
+    }
+}
+
+pub fn hash_content(content: &[u8]) -> String {
+    Sha256::digest(content)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// The history directory, which is only created when a render is recorded.
+fn history_dir() -> Result<PathBuf> {
+    Ok(root::storage_dir()?.join("history"))
+}
+
+fn objects_dir() -> Result<PathBuf> {
+    Ok(history_dir()?.join("objects"))
+}
+
+/// Stores the rendered text of a file under its hash, which is returned, so
+/// later renders can diff against it.
+pub fn write_object(content: &[u8]) -> Result<String> {
+    let hash = hash_content(content);
+    let dir = objects_dir()?;
+    let path = dir.join(&hash);
+    if !path.exists() {
+        fs::create_dir_all(&dir)
+            .wrap_err_with(|| format!("failed to create directory {}", dir.display()))?;
+        fs::write(&path, content)
+            .wrap_err_with(|| format!("failed to write object {}", path.display()))?;
+    }
+    Ok(hash)
+}
+
+pub fn read_object(hash: &str) -> Result<Option<String>> {
+    let path = objects_dir()?.join(hash);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(&path)
+        .wrap_err_with(|| format!("failed to read object {}", path.display()))?;
+    Ok(Some(content))
+}
+
+fn render_dir(id: RenderId) -> Result<PathBuf> {
+    Ok(history_dir()?.join(id.to_string()))
+}
+
+fn manifest_path(dir: &Path) -> PathBuf {
+    dir.join("manifest.toml")
+}
+
+fn prompt_path(dir: &Path) -> PathBuf {
+    dir.join("prompt.md")
+}
+
+fn ids() -> Result<Vec<RenderId>> {
+    let dir = history_dir()?;
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => {
+            return Err(Report::new(e).wrap_err(format!("failed to read {}", dir.display())));
+        }
+    };
+
+    let mut ids = vec![];
+    for entry in entries {
+        let path = entry?.path();
+        if path.is_dir()
+            && let Some(id) = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .and_then(|n| n.parse().ok())
+        {
+            ids.push(id);
+        }
+    }
+    ids.sort_unstable();
+    Ok(ids)
+}
+
+/// Stores the manifest and the exact prompt of a render, assigning it the next
+/// ID and the current time.
+pub fn record(render: NewRender, prompt: &str) -> Result<RenderId> {
+    let manifest = Manifest {
+        id: ids()?.last().map_or(1, |id| id + 1),
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .wrap_err("system clock is set before the unix epoch")?
+            .as_secs(),
+        template: render.template,
+        feature: render.feature,
+        model: render.model,
+        base: render.base,
+        rev: render.rev,
+        token_count: render.token_count,
+        task: render.task,
+        files: render.files,
+    };
+
+    let dir = render_dir(manifest.id)?;
+    fs::create_dir_all(&dir)
+        .wrap_err_with(|| format!("failed to create directory {}", dir.display()))?;
+
+    let content = toml::to_string_pretty(&manifest).wrap_err("failed to serialize manifest")?;
+    fs::write(manifest_path(&dir), content)
+        .wrap_err_with(|| format!("failed to write manifest for render {}", manifest.id))?;
+    fs::write(prompt_path(&dir), prompt)
+        .wrap_err_with(|| format!("failed to write prompt for render {}", manifest.id))?;
+
+    prune_objects()?;
+    Ok(manifest.id)
+}
+
+/// Deletes the objects that no render can be diffed against anymore. Deltas
+/// are only taken against the latest render of each feature (or of the global
+/// selection), so only its objects are kept.
+fn prune_objects() -> Result<()> {
+    let dir = objects_dir()?;
+    if !dir.exists() {
+        return Ok(());
+    }
+
+    let mut latest = HashMap::new();
+    for manifest in list()? {
+        latest.insert(manifest.feature.clone(), manifest);
+    }
+    let kept: HashSet<_> = latest
+        .values()
+        .flat_map(|manifest| &manifest.files)
+        .filter_map(|file| file.object.as_deref())
+        .collect();
+
+    for entry in fs::read_dir(&dir).wrap_err("failed to read history objects")? {
+        let path = entry?.path();
+        let is_kept = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| kept.contains(name));
+        if !is_kept {
+            fs::remove_file(&path)
+                .wrap_err_with(|| format!("failed to delete object {}", path.display()))?;
+        }
+    }
+    Ok(())
+}
+
+pub fn read(id: RenderId) -> Result<Manifest> {
+    let dir = render_dir(id)?;
+    if !dir.exists() {
+        return Err(eyre!("Render {} does not exist in the history.", id));
+    }
+    let content = fs::read_to_string(manifest_path(&dir))
+        .wrap_err_with(|| format!("failed to read manifest for render {}", id))?;
+    toml::from_str(&content).wrap_err_with(|| format!("failed to parse manifest for render {}", id))
+}
+
+pub fn read_prompt(id: RenderId) -> Result<String> {
+    let dir = render_dir(id)?;
+    fs::read_to_string(prompt_path(&dir))
+        .wrap_err_with(|| format!("failed to read prompt for render {}", id))
+}
+
+/// Lists all recorded renders, oldest first. Renders whose manifest can't be
+/// read are reported on stderr and left out.
+pub fn list() -> Result<Vec<Manifest>> {
+    Ok(ids()?.into_iter().filter_map(read_or_report).collect())
+}
+
+/// Returns the most recent render of the given feature, or of the global
+/// selection when `feature` is `None`.
+pub fn latest(feature: Option<&FeatureName>) -> Result<Option<Manifest>> {
+    for id in ids()?.into_iter().rev() {
+        let Some(manifest) = read_or_report(id) else {
+            continue;
+        };
+        if manifest.feature.as_deref() == feature.map(FeatureName::as_str) {
+            return Ok(Some(manifest));
+        }
+    }
+    Ok(None)
+}
+
+fn read_or_report(id: RenderId) -> Option<Manifest> {
+    match read(id) {
+        Ok(manifest) => Some(manifest),
+        Err(e) => {
+            eprintln!("Warning: skipping render {id}: {e:#}");
+            None
+        }
+    }
+}
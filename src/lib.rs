@@ -20,13 +20,22 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
 // THE SOFTWARE.
 
+pub mod anonymize;
+pub mod cargo;
 pub mod cli;
 pub mod command;
 pub mod config;
 pub mod constants;
+pub mod content;
 pub mod editor;
+pub mod exclude;
 pub mod feature;
 pub mod git;
+pub mod history;
+pub mod module_graph;
 pub mod output;
+pub mod root;
+pub mod secrets;
+pub mod task;
 pub mod template;
 pub mod token;
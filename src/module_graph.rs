@@ -0,0 +1,307 @@
+// MIT License
+//
+// Copyright (c) 2025 Dmytro Prokhorov
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use syn::{Attribute, Expr, ExprLit, ItemMod, ItemUse, Lit, Meta, UseTree, visit::Visit};
+
+/// Where the module graph looks up and reads files, e.g. the working tree or
+/// the tree of a git revision.
+pub trait SourceTree {
+    /// Whether `path` is a file.
+    fn is_file(&self, path: &Path) -> bool;
+
+    /// The content of the file at `path`, if it can be read as text.
+    fn read_to_string(&self, path: &Path) -> Option<String>;
+}
+
+/// Follows the module graph of the Rust files `seeds` in `tree`, through `mod`
+/// declarations and `use` or other paths starting with `crate`, `self` or
+/// `super`, to the files of the modules they use, up to `depth` steps away.
+/// Returns the files found, other than the seeds, in the order they were found.
+pub fn expand(tree: &dyn SourceTree, seeds: &[PathBuf], depth: usize) -> Vec<PathBuf> {
+    let mut seen: HashSet<_> = seeds.iter().cloned().collect();
+    let mut found = vec![];
+    let mut frontier = seeds.to_vec();
+
+    for _ in 0..depth {
+        let mut next = vec![];
+        for file in &frontier {
+            for dependency in dependencies(tree, file) {
+                if seen.insert(dependency.clone()) {
+                    found.push(dependency.clone());
+                    next.push(dependency);
+                }
+            }
+        }
+        if next.is_empty() {
+            break;
+        }
+        frontier = next;
+    }
+
+    found
+}
+
+/// Returns the files of the modules that `file` declares or uses. A file that
+/// can't be read or parsed has none.
+fn dependencies(tree: &dyn SourceTree, file: &Path) -> Vec<PathBuf> {
+    let Some(text) = tree.read_to_string(file) else {
+        return vec![];
+    };
+    let Ok(syntax) = syn::parse_file(&text) else {
+        return vec![];
+    };
+
+    let mut visitor = Dependencies {
+        tree,
+        file,
+        crate_root: crate_root(tree, file),
+        dir: module_dir(file),
+        module: module_path(tree, file),
+        files: vec![],
+    };
+    visitor.visit_file(&syntax);
+    drop(syntax);
+    proc_macro2::extra::invalidate_current_thread_spans();
+
+    let mut seen = HashSet::new();
+    visitor
+        .files
+        .retain(|dependency| dependency != file && seen.insert(dependency.clone()));
+    visitor.files
+}
+
+struct Dependencies<'a> {
+    tree: &'a dyn SourceTree,
+    file: &'a Path,
+    /// The `src` directory of the crate, if `file` is in one.
+    crate_root: Option<PathBuf>,
+    /// Where the files of modules declared in the current module are.
+    dir: PathBuf,
+    /// The path of the current module from the crate root, if known.
+    module: Option<Vec<String>>,
+    files: Vec<PathBuf>,
+}
+
+impl Dependencies<'_> {
+    /// Adds the file of the deepest module on `path` that has one of its own.
+    fn add_path(&mut self, path: &[String]) {
+        let (Some(crate_root), Some(module)) = (&self.crate_root, &self.module) else {
+            return;
+        };
+
+        let mut absolute = match path.first().map(String::as_str) {
+            Some("crate") => vec![],
+            Some("self") => module.clone(),
+            Some("super") => {
+                let supers = path
+                    .iter()
+                    .take_while(|segment| *segment == "super")
+                    .count();
+                let Some(len) = module.len().checked_sub(supers) else {
+                    return;
+                };
+                module[..len].to_vec()
+            }
+            _ => return,
+        };
+        absolute.extend(
+            path.iter()
+                .skip_while(|segment| ["crate", "self", "super"].contains(&segment.as_str()))
+                .cloned(),
+        );
+
+        for len in (0..=absolute.len()).rev() {
+            if let Some(file) = module_file(self.tree, crate_root, &absolute[..len]) {
+                self.files.push(file);
+                return;
+            }
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for Dependencies<'_> {
+    fn visit_item_mod(&mut self, item: &'ast ItemMod) {
+        let name = item.ident.to_string();
+        let path_attribute = path_attribute(&item.attrs);
+
+        let Some((_, items)) = &item.content else {
+            let file = match path_attribute {
+                Some(path) => self.file.parent().map(|dir| dir.join(path)),
+                None => [
+                    self.dir.join(format!("{name}.rs")),
+                    self.dir.join(&name).join("mod.rs"),
+                ]
+                .into_iter()
+                .find(|file| self.tree.is_file(file)),
+            };
+            self.files
+                .extend(file.filter(|file| self.tree.is_file(file)));
+            return;
+        };
+
+        // Inline modules nest both their files and their paths.
+        let dir = match path_attribute {
+            Some(path) => self.dir.join(path),
+            None => self.dir.join(&name),
+        };
+        let outer_dir = std::mem::replace(&mut self.dir, dir);
+        if let Some(module) = &mut self.module {
+            module.push(name);
+        }
+        for item in items {
+            self.visit_item(item);
+        }
+        if let Some(module) = &mut self.module {
+            module.pop();
+        }
+        self.dir = outer_dir;
+    }
+
+    fn visit_item_use(&mut self, item: &'ast ItemUse) {
+        let mut paths = vec![];
+        use_paths(&item.tree, &mut vec![], &mut paths);
+        for path in paths {
+            self.add_path(&path);
+        }
+    }
+
+    fn visit_path(&mut self, path: &'ast syn::Path) {
+        if path.leading_colon.is_none() {
+            let segments: Vec<_> = path
+                .segments
+                .iter()
+                .map(|segment| segment.ident.to_string())
+                .collect();
+            self.add_path(&segments);
+        }
+        syn::visit::visit_path(self, path);
+    }
+}
+
+/// Flattens a `use` tree into the paths it imports, without the final names of
+/// renames and globs.
+fn use_paths(tree: &UseTree, prefix: &mut Vec<String>, paths: &mut Vec<Vec<String>>) {
+    match tree {
+        UseTree::Path(path) => {
+            prefix.push(path.ident.to_string());
+            use_paths(&path.tree, prefix, paths);
+            prefix.pop();
+        }
+        UseTree::Name(name) if name.ident == "self" => paths.push(prefix.clone()),
+        UseTree::Name(name) => {
+            let mut path = prefix.clone();
+            path.push(name.ident.to_string());
+            paths.push(path);
+        }
+        UseTree::Rename(rename) => {
+            let mut path = prefix.clone();
+            path.push(rename.ident.to_string());
+            paths.push(path);
+        }
+        UseTree::Glob(_) => paths.push(prefix.clone()),
+        UseTree::Group(group) => {
+            for tree in &group.items {
+                use_paths(tree, prefix, paths);
+            }
+        }
+    }
+}
+
+/// The value of a `#[path = "..."]` attribute.
+fn path_attribute(attrs: &[Attribute]) -> Option<String> {
+    attrs.iter().find_map(|attr| match &attr.meta {
+        Meta::NameValue(meta) if meta.path.is_ident("path") => match &meta.value {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(path),
+                ..
+            }) => Some(path.value()),
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+/// The `src` directory of the package that holds `file`, if any.
+fn crate_root(tree: &dyn SourceTree, file: &Path) -> Option<PathBuf> {
+    file.ancestors()
+        .skip(1)
+        .find(|dir| {
+            dir.file_name() == Some("src".as_ref())
+                && dir
+                    .parent()
+                    .is_some_and(|package| tree.is_file(&package.join("Cargo.toml")))
+        })
+        .map(Path::to_path_buf)
+}
+
+/// The path of the module in `file` from its crate root, e.g. `["config",
+/// "selection"]` for `src/config/selection.rs`. Binaries in `src/bin` are left
+/// out, as their modules aren't laid out from `src`.
+fn module_path(tree: &dyn SourceTree, file: &Path) -> Option<Vec<String>> {
+    let relative = file.strip_prefix(crate_root(tree, file)?).ok()?;
+    if relative.starts_with("bin") {
+        return None;
+    }
+
+    let mut module: Vec<_> = relative
+        .with_extension("")
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    let is_root = matches!(module.as_slice(), [root] if root == "lib" || root == "main");
+    if is_root || module.last().is_some_and(|name| name == "mod") {
+        module.pop();
+    }
+    Some(module)
+}
+
+/// Where the files of the modules that `file` declares are.
+fn module_dir(file: &Path) -> PathBuf {
+    let dir = file.parent().unwrap_or(Path::new(""));
+    match file.file_name().and_then(|name| name.to_str()) {
+        Some("mod.rs" | "lib.rs" | "main.rs") => dir.to_path_buf(),
+        _ => file.with_extension(""),
+    }
+}
+
+/// The file of the module at `module` under `crate_root`, if it has one of its
+/// own rather than being inline.
+fn module_file(tree: &dyn SourceTree, crate_root: &Path, module: &[String]) -> Option<PathBuf> {
+    let candidates = match module.split_last() {
+        None => vec![crate_root.join("lib.rs"), crate_root.join("main.rs")],
+        Some((name, parents)) => {
+            let dir: PathBuf = parents
+                .iter()
+                .fold(crate_root.to_path_buf(), |dir, parent| dir.join(parent));
+            vec![
+                dir.join(format!("{name}.rs")),
+                dir.join(name).join("mod.rs"),
+            ]
+        }
+    };
+    candidates.into_iter().find(|file| tree.is_file(file))
+}
@@ -0,0 +1,182 @@
+// MIT License
+//
+// Copyright (c) 2025 Dmytro Prokhorov
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+use crate::config::clipboard::{ClipboardConfig, ClipboardMode, ClipboardTarget};
+
+use std::{
+    env,
+    fs::OpenOptions,
+    io::{self, Write},
+    process::{Command, Stdio},
+};
+
+use arboard::Clipboard;
+use base64::{Engine, engine::general_purpose::STANDARD};
+use color_eyre::eyre::{Result, WrapErr};
+
+/// Name of the hidden subcommand that runs the background clipboard owner.
+pub const OWNER_SUBCOMMAND: &str = "clipboard-owner";
+
+/// Whether the platform clipboard only lives as long as the process that set it.
+const SELECTION_OWNED_BY_PROCESS: bool = cfg!(all(
+    unix,
+    not(any(
+        target_os = "macos",
+        target_os = "android",
+        target_os = "emscripten"
+    ))
+));
+
+pub fn copy(content: String, config: &ClipboardConfig) -> Result<()> {
+    match config.mode {
+        ClipboardMode::Auto if SELECTION_OWNED_BY_PROCESS && !has_display_server() => {
+            copy_with_osc52(&content, config.target)
+        }
+        ClipboardMode::Auto => {
+            let mode = if SELECTION_OWNED_BY_PROCESS {
+                ClipboardMode::Background
+            } else {
+                ClipboardMode::Immediate
+            };
+            let config = ClipboardConfig { mode, ..*config };
+            copy(content.clone(), &config).or_else(|e| {
+                eprintln!("System clipboard unavailable ({e}); falling back to OSC 52.");
+                copy_with_osc52(&content, config.target)
+            })
+        }
+        ClipboardMode::Background if SELECTION_OWNED_BY_PROCESS => {
+            // Set the contents in this process first, so errors surface here
+            // rather than in the detached owner.
+            set_text(content.clone(), config.target, false)?;
+            spawn_owner(&content, config.target)?;
+            eprintln!("Copied to clipboard.");
+            Ok(())
+        }
+        ClipboardMode::Background | ClipboardMode::Immediate => {
+            set_text(content, config.target, false)?;
+            eprintln!("Copied to clipboard.");
+            Ok(())
+        }
+        ClipboardMode::Wait => {
+            eprintln!("Copied to clipboard. Waiting for another application to take it...");
+            set_text(content, config.target, true)
+        }
+        ClipboardMode::Osc52 => copy_with_osc52(&content, config.target),
+    }
+}
+
+/// Holds `content` in the selection until another application takes ownership.
+/// Runs in the detached process started by the `background` mode.
+pub fn hold(content: String, target: ClipboardTarget) -> Result<()> {
+    set_text(content, target, true)
+}
+
+fn has_display_server() -> bool {
+    ["DISPLAY", "WAYLAND_DISPLAY"]
+        .iter()
+        .any(|var| env::var_os(var).is_some_and(|value| !value.is_empty()))
+}
+
+#[cfg(all(
+    unix,
+    not(any(target_os = "macos", target_os = "android", target_os = "emscripten"))
+))]
+fn set_text(content: String, target: ClipboardTarget, wait: bool) -> Result<()> {
+    use arboard::{LinuxClipboardKind, SetExtLinux};
+
+    let kind = match target {
+        ClipboardTarget::Clipboard => LinuxClipboardKind::Clipboard,
+        ClipboardTarget::Primary => LinuxClipboardKind::Primary,
+    };
+
+    let mut clipboard = Clipboard::new().wrap_err("failed to initialize clipboard")?;
+    let set = clipboard.set().clipboard(kind);
+    let set = if wait { set.wait() } else { set };
+    set.text(content).wrap_err("failed to copy to clipboard")
+}
+
+#[cfg(not(all(
+    unix,
+    not(any(target_os = "macos", target_os = "android", target_os = "emscripten"))
+)))]
+fn set_text(content: String, _target: ClipboardTarget, _wait: bool) -> Result<()> {
+    let mut clipboard = Clipboard::new().wrap_err("failed to initialize clipboard")?;
+    clipboard
+        .set_text(content)
+        .wrap_err("failed to copy to clipboard")
+}
+
+fn spawn_owner(content: &str, target: ClipboardTarget) -> Result<()> {
+    let exe = env::current_exe().wrap_err("failed to locate the current executable")?;
+
+    let mut command = Command::new(exe);
+    command.arg(OWNER_SUBCOMMAND);
+    if target == ClipboardTarget::Primary {
+        command.arg("--primary");
+    }
+    command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+
+    // Detach from the terminal's process group so closing the shell doesn't
+    // take the clipboard down with it.
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+
+    let mut child = command
+        .spawn()
+        .wrap_err("failed to start the clipboard owner process")?;
+
+    let mut stdin = child.stdin.take().expect("stdin was piped");
+    stdin
+        .write_all(content.as_bytes())
+        .wrap_err("failed to hand the contents to the clipboard owner process")?;
+
+    Ok(())
+}
+
+fn copy_with_osc52(content: &str, target: ClipboardTarget) -> Result<()> {
+    let selection = match target {
+        ClipboardTarget::Clipboard => 'c',
+        ClipboardTarget::Primary => 'p',
+    };
+    let mut sequence = format!("\x1b]52;{selection};{}\x07", STANDARD.encode(content));
+
+    // tmux only forwards escape sequences wrapped in its passthrough sequence.
+    if env::var_os("TMUX").is_some() {
+        sequence = format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"));
+    }
+
+    // Write to the terminal directly, since stdout may be redirected.
+    match OpenOptions::new().write(true).open("/dev/tty") {
+        Ok(mut tty) => tty.write_all(sequence.as_bytes()),
+        Err(_) => io::stderr().write_all(sequence.as_bytes()),
+    }
+    .wrap_err("failed to write OSC 52 sequence to the terminal")?;
+
+    eprintln!("Copied to clipboard via the terminal (OSC 52).");
+    Ok(())
+}
@@ -0,0 +1,114 @@
+// MIT License
+//
+// Copyright (c) 2025 Dmytro Prokhorov
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+pub mod clipboard;
+
+use crate::config::ClipboardConfig;
+
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use color_eyre::eyre::{Result, WrapErr, eyre};
+
+/// A destination for a rendered prompt.
+///
+/// Sinks only write the prompt itself to stdout; status messages always go to
+/// stderr so that the prompt can be redirected or piped cleanly.
+#[derive(Clone, Debug)]
+pub enum Sink {
+    Clipboard(ClipboardConfig),
+    Stdout,
+    File(PathBuf),
+    /// A shell command that receives the prompt on its stdin.
+    Pipe(String),
+}
+
+impl Sink {
+    pub fn write(&self, content: &str) -> Result<()> {
+        match self {
+            Sink::Clipboard(config) => clipboard::copy(content.to_owned(), config),
+            Sink::Stdout => write_to_stdout(content),
+            Sink::File(path) => write_to_file(path, content),
+            Sink::Pipe(command) => pipe_to_command(command, content),
+        }
+    }
+}
+
+/// Writes `content` to every sink, in order.
+pub fn emit(sinks: &[Sink], content: &str) -> Result<()> {
+    for sink in sinks {
+        sink.write(content)?;
+    }
+    Ok(())
+}
+
+fn write_to_stdout(content: &str) -> Result<()> {
+    let mut stdout = io::stdout().lock();
+    stdout
+        .write_all(content.as_bytes())
+        .and_then(|_| stdout.flush())
+        .wrap_err("failed to write to stdout")
+}
+
+fn write_to_file(path: &Path, content: &str) -> Result<()> {
+    fs::write(path, content)
+        .wrap_err_with(|| format!("failed to write output to {}", path.display()))?;
+    eprintln!("Wrote {}.", path.display());
+    Ok(())
+}
+
+fn pipe_to_command(command: &str, content: &str) -> Result<()> {
+    let mut child = shell_command(command)
+        .stdin(Stdio::piped())
+        .spawn()
+        .wrap_err_with(|| format!("failed to start `{command}`"))?;
+
+    let mut stdin = child.stdin.take().expect("stdin was piped");
+    stdin
+        .write_all(content.as_bytes())
+        .wrap_err_with(|| format!("failed to write to `{command}`"))?;
+    drop(stdin);
+
+    let status = child
+        .wait()
+        .wrap_err_with(|| format!("failed to wait for `{command}`"))?;
+    if !status.success() {
+        return Err(eyre!("`{}` exited with {}", command, status));
+    }
+    Ok(())
+}
+
+fn shell_command(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    }
+}
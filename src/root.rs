@@ -0,0 +1,228 @@
+// MIT License
+//
+// Copyright (c) 2025 Dmytro Prokhorov
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+use crate::{
+    config::RootsConfig,
+    constants::{ROOT_ENV_VAR, SHARE_STORAGE_CONFIG_KEY, STORAGE_DIRNAME},
+    git,
+};
+
+use std::{
+    env,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use color_eyre::eyre::{Result, WrapErr, eyre};
+
+static OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+static ROOT: OnceLock<PathBuf> = OnceLock::new();
+static STORAGE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Uses `root` as the project root, as given with `--root`.
+pub fn set_override(root: PathBuf) -> Result<()> {
+    let root = root
+        .canonicalize()
+        .wrap_err_with(|| format!("failed to find project root {}", root.display()))?;
+    OVERRIDE
+        .set(root)
+        .map_err(|_| eyre!("the project root was already set"))
+}
+
+/// Returns the directory that selections are relative to and that holds the
+/// `.select/` storage. It is, in order of precedence:
+/// 1. the directory given with `--root` or `SELECT_ROOT`,
+/// 2. the closest directory, from the current one upwards, with a `.select/`,
+///    in the current worktree or, see [`storage_dir`], in the main one,
+/// 3. the root of the git repository,
+/// 4. the current directory.
+pub fn project_root() -> Result<PathBuf> {
+    if let Some(root) = ROOT.get() {
+        return Ok(root.clone());
+    }
+
+    let root = find()?;
+    Ok(ROOT.get_or_init(|| root).clone())
+}
+
+/// The `.select/` directory of the project. In a linked git worktree without
+/// one of its own, this is the one of the main worktree, so that worktrees of
+/// the same repository share their configuration, features, templates and
+/// history. Setting `select.shareStorage` to false in the git configuration
+/// turns this off.
+pub fn storage_dir() -> Result<PathBuf> {
+    if let Some(dir) = STORAGE.get() {
+        return Ok(dir.clone());
+    }
+
+    let root = project_root()?;
+    let mut dir = root.join(STORAGE_DIRNAME);
+    if !dir.is_dir()
+        && let Some(main_dir) = shared_main_dir(&root)?
+    {
+        dir = main_dir.join(STORAGE_DIRNAME);
+    }
+    Ok(STORAGE.get_or_init(|| dir).clone())
+}
+
+/// Returns the directory in the main worktree whose `.select/` is shared with
+/// `dir`, if `dir` is in a linked worktree.
+fn shared_main_dir(dir: &Path) -> Result<Option<PathBuf>> {
+    if !git::is_repo(dir) || !git::config_bool(dir, SHARE_STORAGE_CONFIG_KEY, true)? {
+        return Ok(None);
+    }
+    git::main_worktree_dir(dir)
+}
+
+fn find() -> Result<PathBuf> {
+    if let Some(root) = OVERRIDE.get() {
+        return Ok(root.clone());
+    }
+
+    if let Some(root) = env::var_os(ROOT_ENV_VAR).filter(|root| !root.is_empty()) {
+        let root = PathBuf::from(root);
+        return root.canonicalize().wrap_err_with(|| {
+            format!(
+                "failed to find project root {} from {ROOT_ENV_VAR}",
+                root.display()
+            )
+        });
+    }
+
+    let current_dir = env::current_dir()
+        .and_then(|dir| dir.canonicalize())
+        .wrap_err("failed to get current dir")?;
+
+    if let Some(root) = current_dir
+        .ancestors()
+        .find(|dir| dir.join(STORAGE_DIRNAME).is_dir())
+    {
+        return Ok(root.to_path_buf());
+    }
+
+    // In a linked worktree, the `.select/` may be in the main worktree.
+    if let Some(main_dir) = shared_main_dir(&current_dir)? {
+        let worktree_root = git::repo_root()?;
+        if let Some((root, _)) = current_dir
+            .ancestors()
+            .zip(main_dir.ancestors())
+            .take_while(|(dir, _)| dir.starts_with(&worktree_root))
+            .find(|(_, main_dir)| main_dir.join(STORAGE_DIRNAME).is_dir())
+        {
+            return Ok(root.to_path_buf());
+        }
+    }
+
+    Ok(git::repo_root().unwrap_or(current_dir))
+}
+
+/// The directories of `[roots]`, which selections refer to as `name:path`, and
+/// those of crates selected with `@crate:name`.
+pub struct NamedRoots(Vec<NamedRoot>);
+
+struct NamedRoot {
+    /// The name in `[roots]`, unset for crates.
+    name: Option<String>,
+    /// What paths in the root are written with, e.g. `deps:`.
+    prefix: String,
+    dir: PathBuf,
+}
+
+impl NamedRoots {
+    pub fn new(config: Option<&RootsConfig>) -> Result<Self> {
+        let project_root = project_root()?;
+        let mut roots = vec![];
+        for (name, dir) in config.iter().flat_map(|config| &config.0) {
+            let dir = match dir.strip_prefix('~') {
+                Some(rest) if rest.is_empty() || rest.starts_with('/') => env::home_dir()
+                    .ok_or_else(|| eyre!("failed to find the home directory for root '{name}'"))?
+                    .join(rest.trim_start_matches('/')),
+                _ => project_root.join(dir),
+            };
+            // A missing directory is only an error once it is walked.
+            let dir = dir.canonicalize().unwrap_or(dir);
+            roots.push(NamedRoot {
+                name: Some(name.clone()),
+                prefix: format!("{name}:"),
+                dir,
+            });
+        }
+        Ok(Self(roots))
+    }
+
+    /// Adds the directory of a crate, whose paths are written with `label`,
+    /// e.g. `@crate:clap@4.5.41/src/lib.rs`.
+    pub fn add_crate(&mut self, label: &str, dir: PathBuf) {
+        self.0.push(NamedRoot {
+            name: None,
+            prefix: format!("{label}/"),
+            dir,
+        });
+    }
+
+    /// Whether `name` is one of the `[roots]`.
+    pub fn contains(&self, name: &str) -> bool {
+        self.0.iter().any(|root| root.name.as_deref() == Some(name))
+    }
+
+    /// Returns the absolute path of `path` in the root `name`.
+    pub fn resolve(&self, name: &str, path: &Path) -> Result<PathBuf> {
+        let names: Vec<_> = self
+            .0
+            .iter()
+            .filter_map(|root| root.name.as_deref())
+            .collect();
+        let Some(root) = self
+            .0
+            .iter()
+            .find(|root| root.name.as_deref() == Some(name))
+        else {
+            if names.is_empty() {
+                return Err(eyre!("unknown root '{name}', select.toml has no [roots]"));
+            }
+            return Err(eyre!(
+                "unknown root '{name}', expected one of the [roots] in select.toml: {}",
+                names.join(", ")
+            ));
+        };
+        Ok(root.dir.join(path))
+    }
+
+    /// Returns the prefix of the root that holds `path`, the innermost one if
+    /// roots nest, and the path within it.
+    pub fn locate<'a>(&'a self, path: &'a Path) -> Option<(&'a str, &'a Path)> {
+        self.0
+            .iter()
+            .filter_map(|root| Some((root.prefix.as_str(), path.strip_prefix(&root.dir).ok()?)))
+            .min_by_key(|(_, rest)| rest.components().count())
+    }
+
+    /// Like [`NamedRoots::locate`], written as in selections, e.g.
+    /// `deps:serde/src`.
+    pub fn named_path(&self, path: &Path) -> Option<PathBuf> {
+        let (prefix, rest) = self.locate(path)?;
+        if rest.as_os_str().is_empty() {
+            return Some(PathBuf::from(prefix.trim_end_matches('/')));
+        }
+        Some(PathBuf::from(format!("{prefix}{}", rest.display())))
+    }
+}
@@ -0,0 +1,227 @@
+// MIT License
+//
+// Copyright (c) 2025 Dmytro Prokhorov
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+use crate::config::secrets::{SecretsAction, SecretsConfig};
+
+use std::{
+    collections::HashMap,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::eyre::{Result, WrapErr, eyre};
+use regex::Regex;
+
+/// Well-known credential formats. Where a pattern has a capture group, only
+/// the group is redacted so the surrounding code stays readable.
+const BUILTIN_PATTERNS: &[(&str, &str)] = &[
+    (
+        "private key",
+        r"-----BEGIN [A-Z ]*PRIVATE KEY-----[\s\S]*?-----END [A-Z ]*PRIVATE KEY-----",
+    ),
+    ("aws access key", r"\b((?:AKIA|ASIA)[0-9A-Z]{16})\b"),
+    (
+        "aws secret key",
+        r#"(?i)aws_?secret_?access_?key\s*[:=]\s*["']?([A-Za-z0-9/+=]{40})"#,
+    ),
+    (
+        "github token",
+        r"\b((?:gh[pousr]_[A-Za-z0-9]{36,})|(?:github_pat_[A-Za-z0-9_]{22,}))\b",
+    ),
+    ("gitlab token", r"\b(glpat-[A-Za-z0-9_\-]{20,})\b"),
+    ("slack token", r"\b(xox[abposr]-[A-Za-z0-9-]{10,})\b"),
+    (
+        "stripe key",
+        r"\b((?:sk|rk)_(?:live|test)_[A-Za-z0-9]{20,})\b",
+    ),
+    ("google api key", r"\b(AIza[0-9A-Za-z_\-]{35})\b"),
+    ("api key", r"\b(sk-[A-Za-z0-9_\-]{20,})\b"),
+    (
+        "jwt",
+        r"\b(eyJ[A-Za-z0-9_\-]{10,}\.[A-Za-z0-9_\-]{10,}\.[A-Za-z0-9_\-]{10,})\b",
+    ),
+    (
+        "hard-coded credential",
+        r#"(?i)\b(?:password|passwd|secret|api_?key|access_?token|auth_?token)\b\s*[:=]\s*["']([^"'\s]{8,})["']"#,
+    ),
+];
+
+/// A potential secret found in a file.
+#[derive(Debug, Clone)]
+pub struct Finding {
+    /// Path relative to the repository root.
+    pub path: PathBuf,
+    /// 1-based line number.
+    pub line: usize,
+    pub kind: String,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.path.display(), self.line, self.kind)
+    }
+}
+
+/// Lists the potential secrets found while producing output on stderr, and
+/// fails if `action` says to block such output.
+pub fn report(findings: &[Finding], action: SecretsAction) -> Result<()> {
+    if findings.is_empty() {
+        return Ok(());
+    }
+
+    let blocked = action == SecretsAction::Block;
+    eprintln!(
+        "{} {} potential secret(s):",
+        if blocked { "Found" } else { "Redacted" },
+        findings.len()
+    );
+    for finding in findings {
+        eprintln!("  {finding}");
+    }
+    if blocked {
+        return Err(eyre!(
+            "Output blocked because of potential secrets. Remove them, exclude the files, \
+             or set `secrets.action = \"redact\"` in select.toml."
+        ));
+    }
+    Ok(())
+}
+
+/// Finds and redacts secrets in file contents.
+pub struct Scanner {
+    patterns: Vec<(String, Regex)>,
+    entropy: Option<(Regex, f64)>,
+}
+
+impl Scanner {
+    pub fn new(config: &SecretsConfig) -> Result<Self> {
+        let builtin = BUILTIN_PATTERNS
+            .iter()
+            .map(|(name, regex)| (name.to_string(), regex.to_string()));
+        let custom = config
+            .patterns
+            .iter()
+            .map(|pattern| (pattern.name.clone(), pattern.regex.clone()));
+
+        let patterns = builtin
+            .chain(custom)
+            .map(|(name, regex)| {
+                let regex = Regex::new(&regex)
+                    .wrap_err_with(|| format!("invalid regex for secret pattern '{name}'"))?;
+                Ok((name, regex))
+            })
+            .collect::<Result<_>>()?;
+
+        let entropy = if config.entropy {
+            let candidate = format!(r"[A-Za-z0-9+/=_\-]{{{},}}", config.min_length);
+            Some((
+                Regex::new(&candidate).expect("entropy candidate regex is valid"),
+                config.min_entropy,
+            ))
+        } else {
+            None
+        };
+
+        Ok(Self { patterns, entropy })
+    }
+
+    /// Returns `text` with every detected secret replaced by a marker, along
+    /// with what was found. `path` is only used to label the findings.
+    pub fn redact(&self, path: &Path, text: &str) -> (String, Vec<Finding>) {
+        // Byte ranges to redact, with the kind of secret found there.
+        let mut ranges: Vec<(usize, usize, &str)> = vec![];
+
+        for (name, regex) in &self.patterns {
+            for captures in regex.captures_iter(text) {
+                let m = captures
+                    .get(1)
+                    .unwrap_or_else(|| captures.get(0).expect("group 0 always matches"));
+                ranges.push((m.start(), m.end(), name));
+            }
+        }
+
+        if let Some((ref candidate, min_entropy)) = self.entropy {
+            for m in candidate.find_iter(text) {
+                let overlaps = ranges
+                    .iter()
+                    .any(|&(start, end, _)| m.start() < end && start < m.end());
+                if !overlaps && looks_random(m.as_str(), min_entropy) {
+                    ranges.push((m.start(), m.end(), "high-entropy string"));
+                }
+            }
+        }
+
+        if ranges.is_empty() {
+            return (text.to_owned(), vec![]);
+        }
+
+        ranges.sort_by_key(|&(start, end, _)| (start, std::cmp::Reverse(end)));
+
+        let mut redacted = String::with_capacity(text.len());
+        let mut findings = vec![];
+        let mut cursor = 0;
+        for (start, end, kind) in ranges {
+            // Skip matches nested in one that was already redacted.
+            if start < cursor {
+                continue;
+            }
+            redacted.push_str(&text[cursor..start]);
+            redacted.push_str(&format!("[REDACTED:{kind}]"));
+            cursor = end;
+
+            findings.push(Finding {
+                path: path.to_path_buf(),
+                line: text[..start].matches('\n').count() + 1,
+                kind: kind.to_owned(),
+            });
+        }
+        redacted.push_str(&text[cursor..]);
+
+        (redacted, findings)
+    }
+}
+
+/// Whether a string has high Shannon entropy and mixes character classes the
+/// way generated tokens do, unlike identifiers or hex digests.
+fn looks_random(s: &str, min_entropy: f64) -> bool {
+    let has_lower = s.bytes().any(|b| b.is_ascii_lowercase());
+    let has_upper = s.bytes().any(|b| b.is_ascii_uppercase());
+    let has_digit = s.bytes().any(|b| b.is_ascii_digit());
+    if !(has_lower && has_upper && has_digit) {
+        return false;
+    }
+
+    let mut counts = HashMap::new();
+    for b in s.bytes() {
+        *counts.entry(b).or_insert(0usize) += 1;
+    }
+    let len = s.len() as f64;
+    let entropy: f64 = counts
+        .values()
+        .map(|&count| {
+            let p = count as f64 / len;
+            -p * p.log2()
+        })
+        .sum();
+
+    entropy >= min_entropy
+}
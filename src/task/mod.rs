@@ -0,0 +1,150 @@
+// MIT License
+//
+// Copyright (c) 2025 Dmytro Prokhorov
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+use crate::root;
+
+use std::{
+    fs, io,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use color_eyre::eyre::{Report, Result, WrapErr, eyre};
+
+/// How many tasks the history keeps; older ones are deleted as new ones are added.
+const HISTORY_LIMIT: usize = 100;
+
+fn tasks_dir() -> Result<PathBuf> {
+    Ok(root::storage_dir()?.join("tasks"))
+}
+
+fn draft_path() -> Result<PathBuf> {
+    Ok(tasks_dir()?.join("draft.md"))
+}
+
+fn history_dir() -> Result<PathBuf> {
+    Ok(tasks_dir()?.join("history"))
+}
+
+/// Reads the task that was typed in the editor but never made it into a
+/// successful render.
+pub fn read_draft() -> Result<Option<String>> {
+    let path = draft_path()?;
+    match fs::read_to_string(&path) {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => {
+            Err(Report::new(e)
+                .wrap_err(format!("failed to read task draft from {}", path.display())))
+        }
+    }
+}
+
+pub fn write_draft(task: &str) -> Result<()> {
+    let path = draft_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .wrap_err_with(|| format!("failed to create directories for {}", parent.display()))?;
+    }
+    fs::write(&path, task)
+        .wrap_err_with(|| format!("failed to write task draft to {}", path.display()))
+}
+
+pub fn delete_draft() -> Result<()> {
+    let path = draft_path()?;
+    if path.exists() {
+        fs::remove_file(&path)
+            .wrap_err_with(|| format!("failed to delete task draft {}", path.display()))?;
+    }
+    Ok(())
+}
+
+/// Returns the paths of all tasks in the history, most recent first.
+fn history_paths() -> Result<Vec<PathBuf>> {
+    let dir = history_dir()?;
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => {
+            return Err(
+                Report::new(e).wrap_err(format!("failed to read task history {}", dir.display()))
+            );
+        }
+    };
+
+    let mut paths = vec![];
+    for entry in entries {
+        let path = entry?.path();
+        if path.is_file() {
+            paths.push(path);
+        }
+    }
+    // File names are zero-padded timestamps, so lexical order is chronological.
+    paths.sort();
+    paths.reverse();
+    Ok(paths)
+}
+
+/// Appends a task to the history, unless it is identical to the most recent one,
+/// and drops the oldest tasks beyond [`HISTORY_LIMIT`].
+pub fn push_history(task: &str) -> Result<()> {
+    if task.trim().is_empty() || from_history(1).ok().as_deref() == Some(task) {
+        return Ok(());
+    }
+
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .wrap_err("system clock is set before the unix epoch")?
+        .as_millis();
+
+    let dir = history_dir()?;
+    fs::create_dir_all(&dir).wrap_err("failed to create task history directory")?;
+    let path = dir.join(format!("{millis:020}.md"));
+    fs::write(&path, task)
+        .wrap_err_with(|| format!("failed to write task to {}", path.display()))?;
+
+    for path in history_paths()?.iter().skip(HISTORY_LIMIT) {
+        fs::remove_file(path)
+            .wrap_err_with(|| format!("failed to delete task {}", path.display()))?;
+    }
+    Ok(())
+}
+
+/// Reads the `n`-th most recent task from the history, where `1` is the last one.
+pub fn from_history(n: usize) -> Result<String> {
+    if n == 0 {
+        return Err(eyre!(
+            "Task history is indexed from 1 (the most recent task)."
+        ));
+    }
+
+    let paths = history_paths()?;
+    let path = paths.get(n - 1).ok_or_else(|| {
+        eyre!(
+            "Task #{} is not in the history; only {} task(s) recorded.",
+            n,
+            paths.len()
+        )
+    })?;
+
+    fs::read_to_string(path).wrap_err_with(|| format!("failed to read task {}", path.display()))
+}
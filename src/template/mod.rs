@@ -20,7 +20,7 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
 // THE SOFTWARE.
 
-use crate::git;
+use crate::root;
 
 use std::{fs, path::PathBuf};
 
@@ -50,7 +50,7 @@ impl std::fmt::Display for TemplateName {
 }
 
 fn template_dir() -> Result<PathBuf> {
-    let dir = git::repo_root()?.join(".select").join("templates");
+    let dir = root::storage_dir()?.join("templates");
     if !dir.exists() {
         fs::create_dir_all(&dir).wrap_err("failed to create templates directory")?;
     }
@@ -91,10 +91,10 @@ pub fn list() -> Result<Vec<TemplateName>> {
     for entry in fs::read_dir(dir)? {
         let entry = entry?;
         let path = entry.path();
-        if path.is_file() {
-            if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                names.push(TemplateName::new(name.to_owned()));
-            }
+        if path.is_file()
+            && let Some(name) = path.file_name().and_then(|n| n.to_str())
+        {
+            names.push(TemplateName::new(name.to_owned()));
         }
     }
     names.sort();
@@ -115,6 +115,21 @@ pub fn render<C: Serialize>(name: &TemplateName, ctx: C) -> Result<String> {
     tpl.render(ctx).wrap_err("Failed to render template")
 }
 
+/// Whether the template refers to the context variable `variable`.
+pub fn uses_variable(name: &TemplateName, variable: &str) -> Result<bool> {
+    let content = read(name)?;
+
+    let mut env = Environment::new();
+    env.add_template(name.as_str(), &content)
+        .wrap_err("Failed to parse template")?;
+
+    let tpl = env
+        .get_template(name.as_str())
+        .expect("template was just added");
+
+    Ok(tpl.undeclared_variables(false).contains(variable))
+}
+
 pub fn file_path(name: &TemplateName) -> Result<PathBuf> {
     template_path(name)
 }
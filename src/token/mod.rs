@@ -22,8 +22,11 @@
 
 use std::fmt;
 
+use serde::{Deserialize, Serialize};
+
 /// A newtype for representing an estimated token count.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct TokenCount(pub usize);
 
 impl fmt::Display for TokenCount {